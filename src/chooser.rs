@@ -0,0 +1,77 @@
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Chooser binary used when `--chooser` isn't given and `ENVGEN_CHOOSER`
+/// isn't set.
+const DEFAULT_CHOOSER: &str = "fzf";
+
+/// Environment variable that overrides the default chooser binary.
+const CHOOSER_ENV_VAR: &str = "ENVGEN_CHOOSER";
+
+/// Resolve which chooser binary to invoke: `--chooser` flag, then
+/// `ENVGEN_CHOOSER`, then the `fzf` default.
+pub fn resolve_chooser(explicit: Option<&str>) -> String {
+    explicit
+        .map(str::to_string)
+        .or_else(|| std::env::var(CHOOSER_ENV_VAR).ok())
+        .unwrap_or_else(|| DEFAULT_CHOOSER.to_string())
+}
+
+/// Launch `chooser`, piping `candidates` (one per line) into its stdin, and
+/// return the line the user selected.
+///
+/// Returns `None` whenever an interactive pick isn't possible: stdout isn't
+/// a TTY, the chooser binary can't be found or launched, it exits
+/// unsuccessfully, or nothing was selected — callers should fall back to
+/// their own non-interactive behavior in all of these cases.
+pub fn choose<'a>(chooser: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+
+    let mut child = Command::new(chooser)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    {
+        let stdin = child.stdin.as_mut()?;
+        for candidate in candidates {
+            writeln!(stdin, "{}", candidate).ok()?;
+        }
+    }
+
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let selected = String::from_utf8(output.stdout).ok()?;
+    let selected = selected.trim();
+    if selected.is_empty() {
+        None
+    } else {
+        Some(selected.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::resolve_chooser;
+
+    #[test]
+    fn explicit_flag_wins() {
+        assert_eq!(resolve_chooser(Some("sk")), "sk");
+    }
+
+    #[test]
+    fn defaults_to_fzf() {
+        // SAFETY: no other test reads or writes ENVGEN_CHOOSER, so nothing
+        // reads it concurrently.
+        unsafe {
+            std::env::remove_var("ENVGEN_CHOOSER");
+        }
+        assert_eq!(resolve_chooser(None), "fzf");
+    }
+}
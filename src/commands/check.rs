@@ -22,6 +22,24 @@ pub fn run_check(schema_path: &Path) -> Result<bool> {
                 source_count,
                 if source_count == 1 { "" } else { "s" },
             );
+
+            println!("\nEffective environment config (after `extends` merging):");
+            for env_name in schema.environment_names() {
+                let config = schema
+                    .resolved_env_config(&env_name)
+                    .expect("already validated, so extends chains are known-good");
+                if config.is_empty() {
+                    println!("  {}: (no config)", env_name);
+                } else {
+                    let pairs = config
+                        .iter()
+                        .map(|(k, v)| format!("{}={}", k, v))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    println!("  {}: {}", env_name, pairs);
+                }
+            }
+
             Ok(true)
         }
         SchemaValidation::Invalid(errors) => {
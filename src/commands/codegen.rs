@@ -0,0 +1,304 @@
+use anyhow::{bail, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::schema::types::Schema;
+use crate::schema::validation::{load_and_validate_schema_file, SchemaValidation};
+
+pub struct CodegenOptions {
+    pub schema_path: PathBuf,
+    pub lang: CodegenLang,
+    pub output_path: Option<PathBuf>,
+    pub force: bool,
+    pub quiet: bool,
+}
+
+/// Target language for generated typed bindings.
+pub enum CodegenLang {
+    Rust,
+    TypeScript,
+    EnvExample,
+}
+
+impl CodegenLang {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "rust" => Ok(CodegenLang::Rust),
+            "typescript" => Ok(CodegenLang::TypeScript),
+            "env-example" => Ok(CodegenLang::EnvExample),
+            _ => bail!(
+                "Unknown codegen language: \"{}\". Expected \"rust\", \"typescript\", or \"env-example\".",
+                s
+            ),
+        }
+    }
+
+    fn default_filename(&self) -> &'static str {
+        match self {
+            CodegenLang::Rust => "env.rs",
+            CodegenLang::TypeScript => "env.d.ts",
+            CodegenLang::EnvExample => ".env.example",
+        }
+    }
+}
+
+fn resolve_output_path(output: Option<PathBuf>, lang: &CodegenLang) -> PathBuf {
+    match output {
+        Some(path) => {
+            if path.exists() && path.is_dir() {
+                path.join(lang.default_filename())
+            } else {
+                path
+            }
+        }
+        None => PathBuf::from(lang.default_filename()),
+    }
+}
+
+/// Strict and reserved Rust keywords, lowercased (schema variable names are
+/// lowercased before this is checked against).
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "break", "const", "continue", "dyn", "else", "enum", "fn", "for", "if",
+    "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "type", "unsafe", "use", "where",
+    "while", "async", "await", "abstract", "become", "box", "do", "final",
+    "macro", "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+];
+
+/// Keywords raw identifiers can't escape (`r#self` etc. is itself invalid
+/// syntax), so these get an underscore prefix instead of the `r#` form.
+const RUST_KEYWORDS_NO_RAW: &[&str] = &["self", "super", "crate", "extern", "true", "false"];
+
+/// Converts a schema variable name into a valid, idiomatic Rust field name:
+/// lowercased, with any character that isn't a valid identifier continuation
+/// replaced by `_`, a leading digit or empty result prefixed with `_`, and a
+/// reserved keyword escaped via a raw identifier (or an underscore prefix,
+/// for the handful of keywords raw identifiers can't represent).
+fn rust_field_name(var_name: &str) -> String {
+    let lower = var_name.to_lowercase();
+    let mut sanitized: String = lower
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' })
+        .collect();
+
+    if sanitized.is_empty() || sanitized.starts_with(|c: char| c.is_ascii_digit()) {
+        sanitized = format!("_{}", sanitized);
+    }
+
+    if RUST_KEYWORDS_NO_RAW.contains(&sanitized.as_str()) {
+        format!("_{}", sanitized)
+    } else if RUST_KEYWORDS.contains(&sanitized.as_str()) {
+        format!("r#{}", sanitized)
+    } else {
+        sanitized
+    }
+}
+
+fn render_rust(schema_path: &str, schema: &Schema) -> String {
+    let mut fields = String::new();
+    let mut loader_lines = String::new();
+
+    for (var_name, var) in &schema.variables {
+        let field = rust_field_name(var_name);
+        let ty = if var.required { "String" } else { "Option<String>" };
+
+        fields.push_str(&format!(
+            "    /// {}{}\n    pub {}: {},\n",
+            var.description,
+            if var.sensitive { " (sensitive)" } else { "" },
+            field,
+            ty
+        ));
+
+        if var.required {
+            loader_lines.push_str(&format!(
+                "            {}: std::env::var(\"{}\")?,\n",
+                field, var_name
+            ));
+        } else {
+            loader_lines.push_str(&format!(
+                "            {}: std::env::var(\"{}\").ok(),\n",
+                field, var_name
+            ));
+        }
+    }
+
+    format!(
+        "// Auto-generated by `envgen codegen` from {}. Do not edit by hand.\n\n\
+/// Typed accessors for the environment variables declared in the schema.\n\
+pub struct Env {{\n{}}}\n\n\
+impl Env {{\n    \
+/// Loads all declared variables from the process environment. Fails if a\n    \
+/// required variable is unset.\n    \
+pub fn from_env() -> Result<Self, std::env::VarError> {{\n        Ok(Env {{\n{}        }})\n    }}\n}}\n",
+        schema_path, fields, loader_lines
+    )
+}
+
+fn render_typescript(schema_path: &str, schema: &Schema) -> String {
+    let mut members = String::new();
+
+    for (var_name, var) in &schema.variables {
+        let optional = if var.required { "" } else { "?" };
+        members.push_str(&format!(
+            "  /** {}{} */\n  readonly {}{}: string;\n",
+            var.description,
+            if var.sensitive { " (sensitive)" } else { "" },
+            var_name,
+            optional
+        ));
+    }
+
+    format!(
+        "// Auto-generated by `envgen codegen` from {}. Do not edit by hand.\n\n\
+export interface ImportMetaEnv {{\n{}}}\n\n\
+declare global {{\n  \
+interface ImportMeta {{\n    readonly env: ImportMetaEnv;\n  }}\n  \
+namespace NodeJS {{\n    interface ProcessEnv extends ImportMetaEnv {{}}\n  }}\n}}\n\n\
+export {{}};\n",
+        schema_path, members
+    )
+}
+
+fn render_env_example(schema_path: &str, schema: &Schema) -> String {
+    let mut out = format!(
+        "# Auto-generated by `envgen codegen` from {}. Do not edit by hand.\n",
+        schema_path
+    );
+
+    for (var_name, var) in &schema.variables {
+        out.push('\n');
+        out.push_str(&format!(
+            "# {}{}\n",
+            var.description,
+            if var.sensitive { " (sensitive)" } else { "" }
+        ));
+        out.push_str(&format!("{}=\n", var_name));
+    }
+
+    out
+}
+
+/// Render typed bindings for `schema.variables` in the requested language.
+fn render(lang: &CodegenLang, schema_path: &str, schema: &Schema) -> String {
+    match lang {
+        CodegenLang::Rust => render_rust(schema_path, schema),
+        CodegenLang::TypeScript => render_typescript(schema_path, schema),
+        CodegenLang::EnvExample => render_env_example(schema_path, schema),
+    }
+}
+
+/// Run the `codegen` command: emit typed bindings for a schema's variables
+/// instead of (or alongside) a raw `.env` file.
+pub fn run_codegen(opts: CodegenOptions) -> Result<()> {
+    let schema = match load_and_validate_schema_file(&opts.schema_path)? {
+        SchemaValidation::Valid(schema) => schema,
+        SchemaValidation::Invalid(errors) => {
+            for error in &errors {
+                eprintln!("Error: {}", error);
+            }
+            bail!("Schema validation failed. Fix errors before running `codegen`.");
+        }
+    };
+
+    let dest_path = resolve_output_path(opts.output_path, &opts.lang);
+
+    if dest_path.exists() && dest_path.is_dir() {
+        bail!(
+            "Destination path \"{}\" is a directory. Provide a file path.",
+            dest_path.display()
+        );
+    }
+
+    if dest_path.exists() && !opts.force {
+        bail!(
+            "Destination file \"{}\" already exists. Use --force to overwrite.",
+            dest_path.display()
+        );
+    }
+
+    let content = render(&opts.lang, &opts.schema_path.to_string_lossy(), &schema);
+    fs::write(&dest_path, content)?;
+
+    if !opts.quiet {
+        println!("Wrote typed bindings to {}", dest_path.display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::parser::parse_schema;
+
+    fn schema_fixture() -> Schema {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources: {}
+variables:
+  APP_NAME:
+    description: "App display name"
+    sensitive: false
+    source: static
+    values:
+      local: "demo"
+  API_TOKEN:
+    description: "API token"
+    source: manual
+    required: false
+"#;
+        parse_schema(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_rust_field_name_lowercases() {
+        assert_eq!(rust_field_name("API_TOKEN"), "api_token");
+    }
+
+    #[test]
+    fn test_rust_field_name_sanitizes_invalid_chars() {
+        assert_eq!(rust_field_name("API-KEY"), "api_key");
+    }
+
+    #[test]
+    fn test_rust_field_name_escapes_keyword() {
+        assert_eq!(rust_field_name("TYPE"), "r#type");
+    }
+
+    #[test]
+    fn test_rust_field_name_escapes_unraw_able_keyword() {
+        assert_eq!(rust_field_name("SELF"), "_self");
+    }
+
+    #[test]
+    fn test_render_rust_marks_optional_fields() {
+        let schema = schema_fixture();
+        let out = render_rust("env.yaml", &schema);
+        assert!(out.contains("pub app_name: String,"));
+        assert!(out.contains("pub api_token: Option<String>,"));
+    }
+
+    #[test]
+    fn test_render_typescript_marks_optional_fields() {
+        let schema = schema_fixture();
+        let out = render_typescript("env.yaml", &schema);
+        assert!(out.contains("readonly APP_NAME: string;"));
+        assert!(out.contains("readonly API_TOKEN?: string;"));
+    }
+
+    #[test]
+    fn test_render_env_example_includes_descriptions() {
+        let schema = schema_fixture();
+        let out = render_env_example("env.yaml", &schema);
+        assert!(out.contains("# App display name"));
+        assert!(out.contains("APP_NAME=\n"));
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::Result;
+use clap::Command;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::schema::validation::{load_and_validate_schema_file, SchemaValidation};
+
+/// Print a shell completion script for `cmd` to stdout.
+///
+/// `cmd` is the fully-built `clap::Command` tree (see `Cli::command()` in
+/// `main.rs`); this module only renders it, since the `Parser`-derived `Cli`
+/// struct itself lives in the binary crate root. `--schema <PATH>` already
+/// gets file-path completion for free from clap_complete; for Bash we also
+/// patch the generated script so `--env <NAME>` shells back out to `envgen
+/// __complete environments` and offers the schema's real environment names
+/// instead of falling back to file-path completion.
+pub fn run_completions(mut cmd: Command, shell: clap_complete::Shell) -> Result<()> {
+    let bin_name = cmd.get_name().to_string();
+    let mut buf = Vec::new();
+    clap_complete::generate(shell, &mut cmd, bin_name, &mut buf);
+    let script = String::from_utf8(buf).expect("clap_complete output is always valid UTF-8");
+
+    let script = if shell == clap_complete::Shell::Bash {
+        inject_bash_dynamic_env_completion(&script)
+    } else {
+        script
+    };
+
+    io::stdout().write_all(script.as_bytes())?;
+    Ok(())
+}
+
+/// Rewrite the `--env`/`-e` completion cases clap_complete generates (which
+/// default to file-path completion, since it has no idea those values are
+/// environment names) so they call the hidden `__complete` subcommand and
+/// offer the schema's actual environment names instead.
+///
+/// This is a plain string patch rather than re-deriving the tree, since
+/// clap_complete gives us no hook to customize a single flag's completion
+/// logic. Only Bash is patched for now; other shells keep clap_complete's
+/// static (file-path) completion for `--env`.
+fn inject_bash_dynamic_env_completion(script: &str) -> String {
+    const STATIC_ENV_CASE: &str = "--env)\n                    COMPREPLY=($(compgen -f \"${cur}\"))\n                    return 0\n                    ;;\n                -e)\n                    COMPREPLY=($(compgen -f \"${cur}\"))\n                    return 0\n                    ;;";
+    const DYNAMIC_ENV_CASE: &str = "--env)\n                    COMPREPLY=($(compgen -W \"$(_envgen_complete_environments)\" -- \"${cur}\"))\n                    return 0\n                    ;;\n                -e)\n                    COMPREPLY=($(compgen -W \"$(_envgen_complete_environments)\" -- \"${cur}\"))\n                    return 0\n                    ;;";
+
+    const HELPER_FN: &str = r#"
+# Finds --schema/-s in the current command line and asks envgen for the
+# environment names it declares, so --env can complete with real values.
+_envgen_complete_environments() {
+    local i schema_path=""
+    for ((i = 0; i < ${#COMP_WORDS[@]}; i++)); do
+        if [[ "${COMP_WORDS[i]}" == "--schema" || "${COMP_WORDS[i]}" == "-s" ]]; then
+            schema_path="${COMP_WORDS[i + 1]}"
+            break
+        fi
+    done
+    [[ -n "${schema_path}" ]] && envgen __complete environments --schema "${schema_path}" 2>/dev/null
+}
+"#;
+
+    let patched = script.replace(STATIC_ENV_CASE, DYNAMIC_ENV_CASE);
+    format!("{}\n{}", HELPER_FN, patched)
+}
+
+/// Hidden helper backing dynamic shell completion: `envgen __complete <kind>
+/// --schema PATH` prints one candidate per line (environment or source
+/// names) so completion scripts can shell out to the binary instead of only
+/// offering static subcommands and flags.
+///
+/// Degrades to no output (not an error) whenever the schema can't be loaded
+/// or validated, since a completion request firing mid-edit on a broken
+/// schema shouldn't make the user's shell error out.
+pub fn run_complete(kind: &str, schema_path: Option<PathBuf>) -> Result<()> {
+    let Some(schema_path) = schema_path else {
+        return Ok(());
+    };
+
+    let schema = match load_and_validate_schema_file(&schema_path) {
+        Ok(SchemaValidation::Valid(schema)) => schema,
+        _ => return Ok(()),
+    };
+
+    let mut names: Vec<&String> = match kind {
+        "environments" => schema.environments.keys().collect(),
+        "sources" => schema.sources.keys().collect(),
+        _ => return Ok(()),
+    };
+    names.sort();
+
+    for name in names {
+        println!("{}", name);
+    }
+
+    Ok(())
+}
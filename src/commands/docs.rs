@@ -6,7 +6,11 @@ use crate::output;
 use crate::schema::validation::{load_and_validate_schema_file, SchemaValidation};
 
 /// Run the `docs` command: generate Markdown documentation for a schema file.
-pub fn run_docs(schema_path: &Path, env_filter: Option<&str>) -> Result<()> {
+///
+/// When `annotate_sources` is set, each variable's `### \`VAR\`` section also
+/// lists its effective provenance (static value, manual prompt, `env` var,
+/// or expanded command) per applicable environment.
+pub fn run_docs(schema_path: &Path, env_filter: Option<&str>, annotate_sources: bool) -> Result<()> {
     let schema = match load_and_validate_schema_file(schema_path)? {
         SchemaValidation::Valid(schema) => schema,
         SchemaValidation::Invalid(errors) => {
@@ -30,7 +34,8 @@ pub fn run_docs(schema_path: &Path, env_filter: Option<&str>) -> Result<()> {
         }
     }
 
-    let markdown = output::format_schema_docs_markdown(schema_path, &schema, env_filter)?;
+    let markdown =
+        output::format_schema_docs_markdown(schema_path, &schema, env_filter, annotate_sources)?;
     print!("{}", markdown);
     Ok(())
 }
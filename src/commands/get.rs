@@ -0,0 +1,302 @@
+use anyhow::{bail, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use crate::resolver::safety::{self, CommandAllowlist};
+use crate::resolver::{command_source, env_source, provenance, static_source};
+use crate::schema::types::Schema;
+use crate::schema::validation::{load_and_validate_schema_file, SchemaValidation};
+use crate::template::{self, PlaceholderModifier};
+
+pub struct GetOptions {
+    pub schema_path: PathBuf,
+    pub env_name: String,
+    pub var_name: String,
+    pub source_timeout: u64,
+    /// Print where the value would come from instead of resolving it.
+    pub explain: bool,
+    /// Refuse the variable outright if it uses a command source.
+    pub deny_commands: bool,
+    /// Bypass the allowlist confirmation prompt for a command source (CI mode).
+    pub assume_yes: bool,
+}
+
+/// Run the `get` command: resolve exactly one variable and print only its
+/// raw value to stdout (no masking — the user explicitly asked for it).
+///
+/// Meant for shell usage like `export TOKEN=$(envgen get -e prod API_TOKEN)`,
+/// so nothing but the resolved value is ever written to stdout; errors go to
+/// stderr instead.
+pub async fn run_get(opts: GetOptions) -> Result<bool> {
+    let schema = match load_and_validate_schema_file(&opts.schema_path)? {
+        SchemaValidation::Valid(schema) => schema,
+        SchemaValidation::Invalid(errors) => {
+            for error in &errors {
+                eprintln!("Error: {}", error);
+            }
+            bail!("Schema validation failed. Fix errors before running `get`.");
+        }
+    };
+
+    if !schema.environments.contains_key(&opts.env_name) {
+        let available: Vec<String> = schema.environment_names();
+        let suggestion =
+            crate::suggest::closest(&opts.env_name, available.iter().map(String::as_str));
+        eprintln!(
+            "Error: Environment \"{}\" not found. Available: {}{}",
+            opts.env_name,
+            available.join(", "),
+            match suggestion {
+                Some(name) => format!(" (did you mean \"{}\"?)", name),
+                None => String::new(),
+            }
+        );
+        return Ok(false);
+    }
+
+    let env_config = match schema.resolved_env_config(&opts.env_name) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return Ok(false);
+        }
+    };
+    let env_config = &env_config;
+
+    let var = match schema.variables.get(&opts.var_name) {
+        Some(var) => var,
+        None => {
+            let suggestion = crate::suggest::closest(
+                &opts.var_name,
+                schema.variables.keys().map(String::as_str),
+            );
+            eprintln!(
+                "Error: Variable \"{}\" not found.{}",
+                opts.var_name,
+                match suggestion {
+                    Some(name) => format!(" (did you mean \"{}\"?)", name),
+                    None => String::new(),
+                }
+            );
+            return Ok(false);
+        }
+    };
+
+    if !var.applies_to(&opts.env_name) {
+        eprintln!(
+            "Error: Variable \"{}\" does not apply to environment \"{}\".",
+            opts.var_name, opts.env_name
+        );
+        return Ok(false);
+    }
+
+    let source = match var.effective_source_for_env(&opts.env_name, env_config) {
+        Some(s) => s,
+        None => {
+            eprintln!(
+                "Error: No source configured for \"{}\" in environment \"{}\".",
+                opts.var_name, opts.env_name
+            );
+            return Ok(false);
+        }
+    };
+
+    let key = var.effective_key_for_env(&opts.var_name, &opts.env_name, env_config);
+
+    if opts.explain {
+        return match provenance::describe(&schema, &opts.var_name, var, &opts.env_name, env_config) {
+            Ok(p) => {
+                println!("{}", p.describe_line());
+                Ok(true)
+            }
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                Ok(false)
+            }
+        };
+    }
+
+    // Resolve any `{var:X}` references the variable's own template makes,
+    // so they're available below. Only static/env-sourced dependencies can
+    // be resolved here: `get` fetches one variable at a time and must not
+    // silently execute another command (or block on a manual prompt) as a
+    // side effect of that.
+    let mut resolved_vars: HashMap<String, String> = HashMap::new();
+    let own_template: Option<String> = if source == "static" {
+        var.values_for_env(&opts.env_name, env_config)
+            .and_then(|values| values.get(&opts.env_name))
+            .cloned()
+    } else if source != "manual" && source != "env" {
+        schema.sources.get(source).map(|s| s.command.clone())
+    } else {
+        None
+    };
+    if let Some(template_str) = &own_template {
+        for ph in template::extract_placeholders(template_str) {
+            if ph.modifier == PlaceholderModifier::VariableRef {
+                if let Err(e) = resolve_variable_refs(
+                    &schema,
+                    &opts.env_name,
+                    env_config,
+                    &ph.name,
+                    &mut resolved_vars,
+                ) {
+                    eprintln!("Error: {:#}", e);
+                    return Ok(false);
+                }
+            }
+        }
+    }
+
+    let value = if source == "static" {
+        let values = match var.values_for_env(&opts.env_name, env_config) {
+            Some(values) => values,
+            None => {
+                eprintln!("Error: \"{}\" has no static values map.", opts.var_name);
+                return Ok(false);
+            }
+        };
+        static_source::resolve_static(
+            &opts.var_name,
+            values,
+            &opts.env_name,
+            env_config,
+            &resolved_vars,
+        )
+    } else if source == "manual" {
+        Err(anyhow::anyhow!(
+            "\"{}\" uses a manual source, which `get` doesn't support (it can't prompt while printing a single value to stdout); use `pull --interactive` instead.",
+            opts.var_name
+        ))
+    } else if source == "env" {
+        env_source::resolve_env(&opts.var_name, &key, &env_source::ProcessEnvironment)
+    } else {
+        match schema.sources.get(source) {
+            Some(src) => {
+                let cmd = command_source::build_command(
+                    &src.command,
+                    &opts.var_name,
+                    Some(&key),
+                    &opts.env_name,
+                    env_config,
+                    &resolved_vars,
+                )?;
+                let stdin = command_source::build_stdin(
+                    src.stdin_template.as_deref(),
+                    &opts.var_name,
+                    Some(&key),
+                    &opts.env_name,
+                    env_config,
+                    &resolved_vars,
+                )?;
+                let allowlist = CommandAllowlist::compile(&schema.metadata.command_allowlist)?;
+                match safety::gate_command(
+                    &opts.var_name,
+                    &cmd,
+                    &allowlist,
+                    opts.deny_commands,
+                    opts.assume_yes,
+                )? {
+                    safety::CommandGate::Allowed => command_source::execute_command(
+                        &cmd,
+                        source,
+                        stdin.as_deref(),
+                        opts.source_timeout,
+                        command_source::ShutdownStyle::default(),
+                    )
+                    .await
+                    .map(|result| result.value),
+                    safety::CommandGate::Denied(reason) => Err(anyhow::anyhow!(reason)),
+                }
+            }
+            None => {
+                let suggestion = crate::suggest::closest(source, schema.sources.keys().map(String::as_str));
+                Err(anyhow::anyhow!(
+                    "Source \"{}\" is not defined in sources.{}",
+                    source,
+                    match suggestion {
+                        Some(name) => format!(" (did you mean \"{}\"?)", name),
+                        None => String::new(),
+                    }
+                ))
+            }
+        }
+    };
+
+    match value {
+        Ok(value) => {
+            println!("{}", value);
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Resolves `var_name` and inserts it into `resolved`, recursing first into
+/// any `{var:X}` references its own template makes. Only static/env sources
+/// can be resolved this way; a manual or command-sourced dependency fails
+/// with a clear error instead of silently prompting or running a command.
+fn resolve_variable_refs(
+    schema: &Schema,
+    env_name: &str,
+    env_config: &BTreeMap<String, String>,
+    var_name: &str,
+    resolved: &mut HashMap<String, String>,
+) -> Result<()> {
+    if resolved.contains_key(var_name) {
+        return Ok(());
+    }
+
+    let var = schema
+        .variables
+        .get(var_name)
+        .ok_or_else(|| anyhow::anyhow!("references undefined variable \"{{var:{}}}\"", var_name))?;
+
+    let source = var
+        .effective_source_for_env(env_name, env_config)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "\"{}\" has no source configured for environment \"{}\"",
+                var_name,
+                env_name
+            )
+        })?;
+
+    match source {
+        "static" => {
+            let values = var
+                .values_for_env(env_name, env_config)
+                .ok_or_else(|| anyhow::anyhow!("\"{}\" has no static values map", var_name))?;
+            if let Some(raw_value) = values.get(env_name) {
+                for ph in template::extract_placeholders(raw_value) {
+                    if ph.modifier == PlaceholderModifier::VariableRef {
+                        resolve_variable_refs(schema, env_name, env_config, &ph.name, resolved)?;
+                    }
+                }
+            }
+            let value =
+                static_source::resolve_static(var_name, values, env_name, env_config, resolved)?;
+            resolved.insert(var_name.to_string(), value);
+        }
+        "env" => {
+            let key = var.effective_key_for_env(var_name, env_name, env_config);
+            let value = env_source::resolve_env(var_name, &key, &env_source::ProcessEnvironment)?;
+            resolved.insert(var_name.to_string(), value);
+        }
+        "manual" => bail!(
+            "\"{}\" is referenced via \"{{var:{}}}\" but uses a manual source, which `get` cannot resolve as a dependency.",
+            var_name,
+            var_name
+        ),
+        _ => bail!(
+            "\"{}\" is referenced via \"{{var:{}}}\" but uses a command source, which `get` cannot resolve as a dependency (it only resolves the variable you asked for).",
+            var_name,
+            var_name
+        ),
+    }
+
+    Ok(())
+}
@@ -1,7 +1,21 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use std::fs;
 use std::path::PathBuf;
 
+use crate::schema::parser::{self, SchemaFormat};
+
+/// Header comment prepended to the TOML sample, since `toml::to_string_pretty`
+/// only serializes the data, not the inline comments [`SAMPLE_SCHEMA`] carries.
+const TOML_HEADER_COMMENT: &str = r#"# envgen schema (v2)
+#
+# Next steps:
+# 1. Update metadata.destination to match where you want env files written.
+# 2. Fill in environments.* values (used by {placeholders} in command sources).
+# 3. Add sources and variables for your project.
+# 4. Run: envgen pull --schema env.dev.toml --env dev
+
+"#;
+
 const SAMPLE_SCHEMA: &str = r#"# envgen schema (v2)
 #
 # Next steps:
@@ -78,23 +92,56 @@ pub struct InitOptions {
     pub output_path: Option<PathBuf>,
     pub force: bool,
     pub quiet: bool,
+    /// Explicit format override; defaults to the output path's extension
+    /// (falling back to YAML if there isn't one).
+    pub format: Option<SchemaFormat>,
 }
 
-fn resolve_output_path(output: Option<PathBuf>) -> PathBuf {
+fn resolve_output_path(output: Option<PathBuf>, format: SchemaFormat) -> PathBuf {
+    let filename = format!("env.dev.{}", format.extension());
     match output {
         Some(path) => {
             if path.exists() && path.is_dir() {
-                path.join("env.dev.yaml")
+                path.join(filename)
             } else {
                 path
             }
         }
-        None => PathBuf::from("env.dev.yaml"),
+        None => PathBuf::from(filename),
+    }
+}
+
+/// The canonical sample `Schema` value, parsed from [`SAMPLE_SCHEMA`] so the
+/// TOML and JSON variants are generated from the same data rather than
+/// hand-duplicated.
+fn sample_schema() -> Result<crate::schema::types::Schema> {
+    parser::parse_schema(SAMPLE_SCHEMA).context("embedded sample schema failed to parse")
+}
+
+/// Renders the sample schema in `format`. YAML uses [`SAMPLE_SCHEMA`]
+/// directly so its inline, field-by-field comments survive; TOML gets a
+/// condensed header comment (TOML supports `#` comments, but a pretty-printed
+/// serde value doesn't carry the original ones); JSON gets no comments,
+/// since standard JSON doesn't support them.
+fn render_sample(format: SchemaFormat) -> Result<String> {
+    match format {
+        SchemaFormat::Yaml => Ok(SAMPLE_SCHEMA.to_string()),
+        SchemaFormat::Toml => {
+            let body = parser::serialize_schema(&sample_schema()?, SchemaFormat::Toml)?;
+            Ok(format!("{}{}", TOML_HEADER_COMMENT, body))
+        }
+        SchemaFormat::Json => parser::serialize_schema(&sample_schema()?, SchemaFormat::Json),
     }
 }
 
 pub fn run_init(opts: InitOptions) -> Result<()> {
-    let dest_path = resolve_output_path(opts.output_path);
+    let format = opts
+        .format
+        .unwrap_or_else(|| match &opts.output_path {
+            Some(path) => SchemaFormat::from_extension(path),
+            None => SchemaFormat::Yaml,
+        });
+    let dest_path = resolve_output_path(opts.output_path, format);
 
     if dest_path.exists() && dest_path.is_dir() {
         bail!(
@@ -110,7 +157,7 @@ pub fn run_init(opts: InitOptions) -> Result<()> {
         );
     }
 
-    fs::write(&dest_path, SAMPLE_SCHEMA)?;
+    fs::write(&dest_path, render_sample(format)?)?;
 
     if !opts.quiet {
         println!("Wrote sample schema to {}", dest_path.display());
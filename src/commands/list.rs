@@ -53,6 +53,21 @@ pub fn run_list(schema_path: &Path, env_filter: Option<&str>, format: ListFormat
                 schema_path.display(),
                 schema.metadata.description.trim()
             );
+
+            if let Some(env) = env_filter {
+                let config = schema
+                    .resolved_env_config(env)
+                    .map_err(anyhow::Error::msg)?;
+                if !config.is_empty() {
+                    let pairs = config
+                        .iter()
+                        .map(|(k, v)| format!("{}={}", k, v))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    println!("Effective config for \"{}\" (after `extends` merging): {}\n", env, pairs);
+                }
+            }
+
             let table_output = output::format_variable_table(&schema, env_filter);
             println!("{}", table_output);
         }
@@ -1,9 +1,11 @@
 use anyhow::{bail, Result};
 use colored::Colorize;
+use serde::Serialize;
 use std::path::PathBuf;
 
 use crate::output;
-use crate::resolver::{command_source, manual_source, static_source};
+use crate::resolver::safety::{self, CommandAllowlist};
+use crate::resolver::{command_source, env_source, manual_source, scheduler, static_source};
 use crate::schema::validation::{load_and_validate_schema_file, SchemaValidation};
 use crate::template;
 
@@ -16,6 +18,31 @@ pub struct PullOptions {
     pub interactive: bool,
     pub destination_path: Option<PathBuf>,
     pub source_timeout: u64,
+    /// Maximum number of command sources to run concurrently. `None` uses
+    /// available parallelism (see [`crate::resolver::scheduler::default_parallelism`]).
+    pub max_parallel: Option<usize>,
+    pub watch: bool,
+    pub format: PullFormat,
+    /// Refuse every command source outright; only `static`/`manual`/`env` resolve.
+    pub deny_commands: bool,
+    /// Bypass the allowlist confirmation prompt for command sources (CI mode).
+    pub assume_yes: bool,
+}
+
+/// Output format for the pull command.
+pub enum PullFormat {
+    Pretty,
+    Json,
+}
+
+impl PullFormat {
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pretty" => Ok(PullFormat::Pretty),
+            "json" => Ok(PullFormat::Json),
+            _ => bail!("Unknown format: \"{}\". Expected \"pretty\" or \"json\".", s),
+        }
+    }
 }
 
 /// A resolved variable result.
@@ -25,6 +52,73 @@ enum ResolveResult {
     Failed(String, String),  // (var_name, error)
 }
 
+/// A single variable's outcome, for `--format json`.
+#[derive(Serialize)]
+struct PullVariableReport {
+    name: String,
+    source: String,
+    status: &'static str, // "success" | "skipped" | "failed"
+    reason: Option<String>,
+    value: Option<String>,
+    required: bool,
+    masked: bool,
+}
+
+/// Top-level `--format json` payload.
+#[derive(Serialize)]
+struct PullReport {
+    variables: Vec<PullVariableReport>,
+    summary: PullSummary,
+}
+
+#[derive(Serialize)]
+struct PullSummary {
+    total: usize,
+    success: usize,
+    skipped: usize,
+    failed: usize,
+    destination: String,
+    written: bool,
+    exit_code: i32,
+}
+
+impl PullReport {
+    fn new(variables: Vec<PullVariableReport>, destination: String, written: bool) -> Self {
+        let total = variables.len();
+        let success = variables.iter().filter(|v| v.status == "success").count();
+        let skipped = variables.iter().filter(|v| v.status == "skipped").count();
+        let failed = variables.iter().filter(|v| v.status == "failed").count();
+        let exit_code = if failed > 0 { 1 } else { 0 };
+
+        PullReport {
+            variables,
+            summary: PullSummary {
+                total,
+                success,
+                skipped,
+                failed,
+                destination,
+                written,
+                exit_code,
+            },
+        }
+    }
+
+    fn print(&self) -> Result<()> {
+        println!("{}", serde_json::to_string_pretty(self)?);
+        Ok(())
+    }
+}
+
+/// Format a `" (did you mean \"<name>\"?)"` hint for an unknown source name,
+/// or an empty string when nothing in `schema.sources` is close enough.
+fn suggest_source(schema: &crate::schema::types::Schema, source: &str) -> String {
+    match crate::suggest::closest(source, schema.sources.keys().map(String::as_str)) {
+        Some(name) => format!(" (did you mean \"{}\"?)", name),
+        None => String::new(),
+    }
+}
+
 fn print_labeled_multiline(indent: &str, label: &str, value: &str) {
     let value = value.trim();
     if value.is_empty() {
@@ -44,7 +138,26 @@ fn print_labeled_multiline(indent: &str, label: &str, value: &str) {
 }
 
 /// Run the `pull` command: resolve variables and write the .env file.
+///
+/// In `--watch` mode, runs one pull cycle and then hands off to
+/// [`crate::commands::watch::run_watch`], which keeps re-running
+/// [`run_pull_once`] whenever the schema file changes.
 pub async fn run_pull(opts: PullOptions) -> Result<bool> {
+    if opts.watch && opts.dry_run {
+        bail!("--watch cannot be combined with --dry-run.");
+    }
+
+    let ok = run_pull_once(&opts).await?;
+
+    if !opts.watch {
+        return Ok(ok);
+    }
+
+    super::watch::run_watch(&opts).await
+}
+
+/// Run a single pull cycle: resolve variables and write the .env file.
+pub(super) async fn run_pull_once(opts: &PullOptions) -> Result<bool> {
     // Parse and validate schema
     let schema = match load_and_validate_schema_file(&opts.schema_path)? {
         SchemaValidation::Valid(schema) => schema,
@@ -60,14 +173,27 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
     // Validate environment
     if !schema.environments.contains_key(&opts.env_name) {
         let available: Vec<String> = schema.environment_names();
+        let suggestion = crate::suggest::closest(
+            &opts.env_name,
+            available.iter().map(String::as_str),
+        );
         bail!(
-            "Environment \"{}\" not found. Available: {}",
+            "Environment \"{}\" not found. Available: {}{}",
             opts.env_name,
-            available.join(", ")
+            available.join(", "),
+            match suggestion {
+                Some(name) => format!(" (did you mean \"{}\"?)", name),
+                None => String::new(),
+            }
         );
     }
 
-    let env_config = schema.environments.get(&opts.env_name).unwrap();
+    let env_config = schema
+        .resolved_env_config(&opts.env_name)
+        .map_err(anyhow::Error::msg)?;
+    let env_config = &env_config;
+
+    let allowlist = CommandAllowlist::compile(&schema.metadata.command_allowlist)?;
 
     // Determine destination path
     let dest_path = if let Some(ref destination) = opts.destination_path {
@@ -116,64 +242,96 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
 
     // Dry run header
     if opts.dry_run {
-        println!();
-        println!("Schema:      {}", opts.schema_path.display());
-        println!("Environment: {}", opts.env_name);
-        let exists_str = if dest_path.exists() {
-            "exists"
-        } else {
-            "does not exist"
-        };
-        println!("Destination: {} ({})", dest_path.display(), exists_str);
-        println!();
-        println!("Variables to resolve:");
-        println!();
+        let pretty = matches!(opts.format, PullFormat::Pretty);
+
+        if pretty {
+            println!();
+            println!("Schema:      {}", opts.schema_path.display());
+            println!("Environment: {}", opts.env_name);
+            let exists_str = if dest_path.exists() {
+                "exists"
+            } else {
+                "does not exist"
+            };
+            println!("Destination: {} ({})", dest_path.display(), exists_str);
+            println!();
+            println!("Variables to resolve:");
+            println!();
+        }
 
         let mut command_count = 0;
         let mut static_manual_count = 0;
         let mut would_write_count = 0;
         let mut failed_required = 0;
+        let mut reports: Vec<PullVariableReport> = Vec::new();
 
-        for (var_name, var) in &schema.variables {
-            if !var.applies_to(&opts.env_name) {
-                continue;
-            }
+        // Same dependency ordering as the live resolution path below, so a
+        // previewed static/env value is available for a later variable's
+        // `{var:X}` reference.
+        let dependency_order = crate::schema::dependency::analyze(&schema, &opts.env_name).order;
+        let mut resolved_vars: std::collections::HashMap<String, String> =
+            std::collections::HashMap::new();
+
+        for var_name in &dependency_order {
+            let var = &schema.variables[var_name];
 
-            let source = match var.effective_source_for_env(&opts.env_name) {
+            let source = match var.effective_source_for_env(&opts.env_name, env_config) {
                 Some(s) => s,
                 None => {
-                    println!("  {}", var_name);
-                    println!("    source:  <missing>");
-                    println!(
-                        "    error:   {}",
-                        "No source configured for this variable/environment"
-                    );
-                    println!();
+                    if pretty {
+                        println!("  {}", var_name);
+                        println!("    source:  <missing>");
+                        println!(
+                            "    error:   {}",
+                            "No source configured for this variable/environment"
+                        );
+                        println!();
+                    }
                     if var.required {
                         failed_required += 1;
                     }
+                    reports.push(PullVariableReport {
+                        name: var_name.clone(),
+                        source: "<missing>".to_string(),
+                        status: if var.required { "failed" } else { "skipped" },
+                        reason: Some(
+                            "No source configured for this variable/environment".to_string(),
+                        ),
+                        value: None,
+                        required: var.required,
+                        masked: false,
+                    });
                     continue;
                 }
             };
             if source == "static" {
                 static_manual_count += 1;
-                let key = var.effective_key_for_env(var_name, &opts.env_name);
-                let (value, ok) = match var.values_for_env(&opts.env_name) {
+                let masked = var.sensitive && !opts.show_secrets;
+                let (value, ok) = match var.values_for_env(&opts.env_name, env_config) {
                     Some(values) => match static_source::resolve_static(
                         var_name,
-                        &key,
                         values,
                         &opts.env_name,
                         env_config,
+                        &resolved_vars,
                     ) {
-                        Ok(v) => {
-                            let shown_value = if var.sensitive {
-                                output::mask_value(&v, opts.show_secrets)
-                            } else {
-                                v
-                            };
-                            (shown_value, true)
-                        }
+                        Ok(v) => match var.constraints.as_ref().map(|c| c.check(&v)) {
+                            Some(Err(violation)) => {
+                                if var.required {
+                                    failed_required += 1;
+                                }
+                                (format!("<constraint violation: {}>", violation), false)
+                            }
+                            _ => {
+                                resolved_vars.insert(var_name.clone(), v.clone());
+                                let shown_value = if var.sensitive {
+                                    output::mask_value(&v, opts.show_secrets)
+                                } else {
+                                    v
+                                };
+                                (shown_value, true)
+                            }
+                        },
                         Err(e) => {
                             if var.required {
                                 failed_required += 1;
@@ -188,72 +346,240 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
                         ("<missing>".to_string(), false)
                     }
                 };
-                println!("  {}", var_name);
-                println!("    source:  static");
-                println!("    value:   {}", value);
-                println!();
+                if pretty {
+                    println!("  {}", var_name);
+                    println!("    source:  static");
+                    println!("    value:   {}", value);
+                    println!();
+                }
                 if ok {
                     would_write_count += 1;
                 }
+                reports.push(PullVariableReport {
+                    name: var_name.clone(),
+                    source: "static".to_string(),
+                    status: if ok {
+                        "success"
+                    } else if var.required {
+                        "failed"
+                    } else {
+                        "skipped"
+                    },
+                    reason: if ok { None } else { Some(value.clone()) },
+                    value: if ok { Some(value) } else { None },
+                    required: var.required,
+                    masked: ok && masked,
+                });
+            } else if source == "env" {
+                static_manual_count += 1;
+                let key = var.effective_key_for_env(var_name, &opts.env_name, env_config);
+                let masked = var.sensitive && !opts.show_secrets;
+                let (value, ok) = match env_source::resolve_env(
+                    var_name,
+                    &key,
+                    &env_source::ProcessEnvironment,
+                ) {
+                    Ok(v) => {
+                        resolved_vars.insert(var_name.clone(), v.clone());
+                        let shown_value = if var.sensitive {
+                            output::mask_value(&v, opts.show_secrets)
+                        } else {
+                            v
+                        };
+                        (shown_value, true)
+                    }
+                    Err(e) => {
+                        if var.required {
+                            failed_required += 1;
+                        }
+                        (format!("<error: {}>", e), false)
+                    }
+                };
+                if pretty {
+                    println!("  {}", var_name);
+                    println!("    source:  env");
+                    println!("    value:   {}", value);
+                    println!();
+                }
+                if ok {
+                    would_write_count += 1;
+                }
+                reports.push(PullVariableReport {
+                    name: var_name.clone(),
+                    source: "env".to_string(),
+                    status: if ok {
+                        "success"
+                    } else if var.required {
+                        "failed"
+                    } else {
+                        "skipped"
+                    },
+                    reason: if ok { None } else { Some(value.clone()) },
+                    value: if ok { Some(value) } else { None },
+                    required: var.required,
+                    masked: ok && masked,
+                });
             } else if source == "manual" {
                 static_manual_count += 1;
-                println!("  {}", var_name);
+                if pretty {
+                    println!("  {}", var_name);
+                }
+                let default = var.default_for_env(&opts.env_name, env_config);
                 if opts.interactive {
-                    println!("    source:  manual (interactive prompt)");
+                    if pretty {
+                        println!("    source:  manual (interactive prompt)");
+                    }
                     would_write_count += 1;
-                } else {
+                } else if let Some(default) = default {
+                    if pretty {
+                        println!("    source:  manual (default: {})", default);
+                    }
+                    would_write_count += 1;
+                } else if pretty {
                     println!("    source:  manual (skipped; use --interactive to prompt)");
                 }
-                if let Some(instructions) = &var.source_instructions {
-                    let key = var.effective_key_for_env(var_name, &opts.env_name);
-                    let ctx = template::build_context(&opts.env_name, env_config, &key);
-                    let expanded = template::expand_template_best_effort(instructions, &ctx);
-                    print_labeled_multiline("    ", "instructions", &expanded);
+                if pretty {
+                    if let Some(instructions) = &var.source_instructions {
+                        let key = var.effective_key_for_env(var_name, &opts.env_name, env_config);
+                        let ctx = template::build_context(&opts.env_name, env_config, &key);
+                        let expanded = template::expand_template_best_effort(instructions, &ctx);
+                        print_labeled_multiline("    ", "instructions", &expanded);
+                    }
+                    println!();
+                }
+                let resolves_without_prompt = opts.interactive || default.is_some();
+                let masked = var.sensitive && !opts.show_secrets;
+                let shown_default = if opts.interactive {
+                    None
+                } else if var.sensitive {
+                    default.map(|d| output::mask_value(d, opts.show_secrets))
+                } else {
+                    default.map(|d| d.to_string())
+                };
+                reports.push(PullVariableReport {
+                    name: var_name.clone(),
+                    source: "manual".to_string(),
+                    status: if resolves_without_prompt { "success" } else { "skipped" },
+                    reason: if resolves_without_prompt {
+                        None
+                    } else {
+                        Some("skipped; use --interactive to prompt".to_string())
+                    },
+                    masked: masked && shown_default.is_some(),
+                    value: shown_default,
+                    required: var.required,
+                });
+            } else if opts.deny_commands {
+                static_manual_count += 1;
+                let reason = format!(
+                    "\"{}\" uses a command source, which is refused by --deny-commands.",
+                    var_name
+                );
+                if pretty {
+                    println!("  {}", var_name);
+                    println!("    source:  {}", source);
+                    println!("    error:   {}", reason);
+                    println!();
+                }
+                if var.required {
+                    failed_required += 1;
                 }
-                println!();
+                reports.push(PullVariableReport {
+                    name: var_name.clone(),
+                    source: source.to_string(),
+                    status: if var.required { "failed" } else { "skipped" },
+                    reason: Some(reason),
+                    value: None,
+                    required: var.required,
+                    masked: false,
+                });
             } else {
                 match schema.sources.get(source) {
                     Some(src) => {
-                        let key = var.effective_key_for_env(var_name, &opts.env_name);
-                        let cmd = match command_source::build_command(
+                        let key = var.effective_key_for_env(var_name, &opts.env_name, env_config);
+                        let (cmd, ok) = match command_source::build_command(
                             &src.command,
                             var_name,
                             Some(&key),
                             &opts.env_name,
                             env_config,
+                            &resolved_vars,
                         ) {
                             Ok(cmd) => {
                                 command_count += 1;
                                 would_write_count += 1;
-                                cmd
+                                (cmd, true)
                             }
                             Err(e) => {
                                 if var.required {
                                     failed_required += 1;
                                 }
-                                format!("<error: {}>", e)
+                                (format!("<error: {}>", e), false)
                             }
                         };
 
-                        println!("  {}", var_name);
-                        println!("    source:  {}", source);
-                        println!("    command: {}", cmd);
-                        println!();
+                        if pretty {
+                            println!("  {}", var_name);
+                            println!("    source:  {}", source);
+                            println!("    command: {}", cmd);
+                            if ok && !allowlist.allows(&cmd) {
+                                println!(
+                                    "    note:    not in command_allowlist; will prompt for confirmation unless run with --yes"
+                                );
+                            }
+                            println!();
+                        }
+                        reports.push(PullVariableReport {
+                            name: var_name.clone(),
+                            source: source.to_string(),
+                            status: if ok {
+                                "success"
+                            } else if var.required {
+                                "failed"
+                            } else {
+                                "skipped"
+                            },
+                            reason: if ok { None } else { Some(cmd) },
+                            value: None,
+                            required: var.required,
+                            masked: false,
+                        });
                     }
                     None => {
-                        println!("  {}", var_name);
-                        println!("    source:  {}", source);
-                        println!("    command: <missing>");
-                        println!("    error:   Source \"{}\" is not defined in sources.", source);
-                        println!();
+                        let reason = format!(
+                            "Source \"{}\" is not defined in sources.{}",
+                            source,
+                            suggest_source(&schema, source)
+                        );
+                        if pretty {
+                            println!("  {}", var_name);
+                            println!("    source:  {}", source);
+                            println!("    command: <missing>");
+                            println!("    error:   {}", reason);
+                            println!();
+                        }
                         if var.required {
                             failed_required += 1;
                         }
+                        reports.push(PullVariableReport {
+                            name: var_name.clone(),
+                            source: source.to_string(),
+                            status: if var.required { "failed" } else { "skipped" },
+                            reason: Some(reason),
+                            value: None,
+                            required: var.required,
+                            masked: false,
+                        });
                     }
                 }
             }
         }
 
+        if !pretty {
+            PullReport::new(reports, dest_path.to_string_lossy().to_string(), false).print()?;
+            return Ok(failed_required == 0);
+        }
+
         if would_write_count > 0 {
             println!(
                 "{} variable{} would be written to {}",
@@ -279,27 +605,52 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
         return Ok(true);
     }
 
-    // Count applicable variables
-    let applicable_vars: Vec<(&String, &crate::schema::types::Variable)> = schema
-        .variables
+    // Count applicable variables, ordered so a variable's `{var:X}`
+    // cross-references (see `crate::schema::dependency`) are always resolved
+    // before the variable that references them. Schema validation already
+    // rejects undefined references and reference cycles, so the order here
+    // is assumed complete and acyclic.
+    let dependency_order = crate::schema::dependency::analyze(&schema, &opts.env_name).order;
+    let applicable_vars: Vec<(&String, &crate::schema::types::Variable)> = dependency_order
         .iter()
-        .filter(|(_, v)| v.applies_to(&opts.env_name))
+        .filter_map(|name| schema.variables.get_key_value(name))
         .collect();
 
-    println!(
-        "\nPulling {} variable{} for environment \"{}\"...\n",
-        applicable_vars.len(),
-        if applicable_vars.len() == 1 { "" } else { "s" },
-        opts.env_name
-    );
+    let pretty = matches!(opts.format, PullFormat::Pretty);
+
+    if pretty {
+        println!(
+            "\nPulling {} variable{} for environment \"{}\"...\n",
+            applicable_vars.len(),
+            if applicable_vars.len() == 1 { "" } else { "s" },
+            opts.env_name
+        );
+    }
 
     // Collect commands to run in parallel
-    let mut command_tasks: Vec<(String, String, String, bool)> = Vec::new(); // (var_name, source_name, command, required)
+    let mut command_tasks: Vec<(String, String, String, Option<String>, bool)> = Vec::new(); // (var_name, source_name, command, stdin, required)
     let mut static_results: Vec<ResolveResult> = Vec::new();
-    let mut manual_vars: Vec<(String, String, String, Option<String>, bool, bool)> = Vec::new(); // (var_name, key, description, instructions, required, sensitive)
+    #[allow(clippy::type_complexity)]
+    let mut manual_vars: Vec<(
+        String,
+        String,
+        String,
+        Option<String>,
+        bool,
+        bool,
+        Option<Vec<String>>,
+        Option<String>,
+        Option<String>,
+    )> = Vec::new(); // (var_name, key, description, instructions, required, sensitive, choices, pattern, default)
+
+    // Tracks already-resolved static/env values so later variables in
+    // `applicable_vars` can reference them via `{var:X}`. Command sources
+    // resolve afterward in parallel and are never visible here.
+    let mut resolved_vars: std::collections::HashMap<String, String> =
+        std::collections::HashMap::new();
 
     for (var_name, var) in &applicable_vars {
-        let source = match var.effective_source_for_env(&opts.env_name) {
+        let source = match var.effective_source_for_env(&opts.env_name, env_config) {
             Some(s) => s,
             None => {
                 static_results.push(ResolveResult::Failed(
@@ -310,16 +661,16 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
             }
         };
         if source == "static" {
-            let key = var.effective_key_for_env(var_name, &opts.env_name);
-            match var.values_for_env(&opts.env_name) {
+            match var.values_for_env(&opts.env_name, env_config) {
                 Some(values) => match static_source::resolve_static(
                     var_name,
-                    &key,
                     values,
                     &opts.env_name,
                     env_config,
+                    &resolved_vars,
                 ) {
                     Ok(value) => {
+                        resolved_vars.insert(var_name.to_string(), value.clone());
                         static_results.push(ResolveResult::Success(var_name.to_string(), value));
                     }
                     Err(e) => {
@@ -334,8 +685,19 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
                     ));
                 }
             }
+        } else if source == "env" {
+            let key = var.effective_key_for_env(var_name, &opts.env_name, env_config);
+            match env_source::resolve_env(var_name, &key, &env_source::ProcessEnvironment) {
+                Ok(value) => {
+                    resolved_vars.insert(var_name.to_string(), value.clone());
+                    static_results.push(ResolveResult::Success(var_name.to_string(), value));
+                }
+                Err(e) => {
+                    static_results.push(ResolveResult::Failed(var_name.to_string(), e.to_string()));
+                }
+            }
         } else if source == "manual" {
-            let key = var.effective_key_for_env(var_name, &opts.env_name);
+            let key = var.effective_key_for_env(var_name, &opts.env_name, env_config);
             manual_vars.push((
                 var_name.to_string(),
                 key,
@@ -343,59 +705,121 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
                 var.source_instructions.clone(),
                 var.required,
                 var.sensitive,
+                var.choices_for_env(&opts.env_name, env_config).map(|c| c.to_vec()),
+                var.pattern_for_env(&opts.env_name, env_config).map(|p| p.to_string()),
+                var.default_for_env(&opts.env_name, env_config).map(|d| d.to_string()),
             ));
         } else if let Some(src) = schema.sources.get(source) {
-            let key = var.effective_key_for_env(var_name, &opts.env_name);
+            let key = var.effective_key_for_env(var_name, &opts.env_name, env_config);
             match command_source::build_command(
                 &src.command,
                 var_name,
                 Some(&key),
                 &opts.env_name,
                 env_config,
-            ) {
-                Ok(cmd) => {
-                    command_tasks.push((
-                        var_name.to_string(),
-                        source.to_string(),
-                        cmd,
-                        var.required,
-                    ));
+                &resolved_vars,
+            )
+            .and_then(|cmd| {
+                let stdin = command_source::build_stdin(
+                    src.stdin_template.as_deref(),
+                    var_name,
+                    Some(&key),
+                    &opts.env_name,
+                    env_config,
+                    &resolved_vars,
+                )?;
+                Ok((cmd, stdin))
+            }) {
+                Ok((cmd, stdin)) => {
+                    match safety::gate_command(
+                        var_name,
+                        &cmd,
+                        &allowlist,
+                        opts.deny_commands,
+                        opts.assume_yes,
+                    ) {
+                        Ok(safety::CommandGate::Allowed) => {
+                            command_tasks.push((
+                                var_name.to_string(),
+                                source.to_string(),
+                                cmd,
+                                stdin,
+                                var.required,
+                            ));
+                        }
+                        Ok(safety::CommandGate::Denied(reason)) => {
+                            if var.required {
+                                static_results
+                                    .push(ResolveResult::Failed(var_name.to_string(), reason));
+                            } else {
+                                static_results
+                                    .push(ResolveResult::Skipped(var_name.to_string(), reason));
+                            }
+                        }
+                        Err(e) => {
+                            static_results
+                                .push(ResolveResult::Failed(var_name.to_string(), e.to_string()));
+                        }
+                    }
                 }
                 Err(e) => {
                     static_results.push(ResolveResult::Failed(var_name.to_string(), e.to_string()));
                 }
             }
+        } else {
+            static_results.push(ResolveResult::Failed(
+                var_name.to_string(),
+                format!(
+                    "Source \"{}\" is not defined in sources.{}",
+                    source,
+                    suggest_source(&schema, source)
+                ),
+            ));
         }
     }
 
-    // Execute all command tasks in parallel
-    let mut handles = Vec::new();
-    for (var_name, _source_name, cmd, required) in command_tasks {
-        let timeout = opts.source_timeout;
-        handles.push(tokio::spawn(async move {
-            match command_source::execute_command(&cmd, timeout).await {
-                Ok(result) => ResolveResult::Success(var_name, result.value),
-                Err(e) => {
-                    if required {
-                        ResolveResult::Failed(var_name, e.to_string())
-                    } else {
-                        ResolveResult::Skipped(var_name, e.to_string())
-                    }
-                }
-            }
-        }));
-    }
+    // Execute all command tasks concurrently, bounded by --jobs (default:
+    // available parallelism), so a schema with dozens of secrets doesn't
+    // shell out to all of them at once.
+    let required_by_var: std::collections::HashMap<String, bool> = command_tasks
+        .iter()
+        .map(|(var_name, _source_name, _cmd, _stdin, required)| (var_name.clone(), *required))
+        .collect();
+    let jobs: Vec<scheduler::CommandJob> = command_tasks
+        .into_iter()
+        .map(
+            |(var_name, source_name, cmd, stdin, _required)| scheduler::CommandJob {
+                var_name,
+                label: source_name,
+                command: cmd,
+                stdin,
+                timeout_secs: opts.source_timeout,
+                shutdown: command_source::ShutdownStyle::default(),
+            },
+        )
+        .collect();
+    let max_parallel = opts.max_parallel.unwrap_or_else(scheduler::default_parallelism);
+    let report = scheduler::run_command_jobs(jobs, max_parallel).await;
 
     let mut all_results: Vec<ResolveResult> = static_results;
-
-    // Collect parallel results
-    for handle in handles {
-        let result = handle.await?;
-        all_results.push(result);
+    for (var_name, result) in report.results {
+        let required = required_by_var.get(&var_name).copied().unwrap_or(true);
+        all_results.push(match result {
+            Ok(cmd_result) => ResolveResult::Success(var_name, cmd_result.value),
+            Err(e) => {
+                if required {
+                    ResolveResult::Failed(var_name, e.to_string())
+                } else {
+                    ResolveResult::Skipped(var_name, e.to_string())
+                }
+            }
+        });
     }
 
     // Handle manual prompts (must be sequential)
-    for (var_name, key, description, instructions, required, sensitive) in manual_vars {
+    for (var_name, key, description, instructions, required, sensitive, choices, pattern, default) in
+        manual_vars
+    {
         match manual_source::resolve_manual(manual_source::ManualResolveOptions {
             var_name: &var_name,
             key: &key,
@@ -405,6 +829,9 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
             env_config,
             sensitive,
             non_interactive: !opts.interactive,
+            choices: choices.as_deref(),
+            pattern: pattern.as_deref(),
+            default: default.as_deref(),
         }) {
             Ok(Some(value)) => {
                 all_results.push(ResolveResult::Success(var_name, value));
@@ -449,39 +876,83 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
         result_map.insert(name, result);
     }
 
+    let mut reports: Vec<PullVariableReport> = Vec::new();
+
     for var_name in &var_order {
         let var = schema.variables.get(var_name).unwrap();
         let source_display = var
-            .effective_source_for_env(&opts.env_name)
+            .effective_source_for_env(&opts.env_name, env_config)
             .unwrap_or("<missing>");
 
         if let Some(result) = result_map.remove(var_name) {
+            let result = match result {
+                ResolveResult::Success(name, value) => {
+                    match var.constraints.as_ref().and_then(|c| c.check(&value).err()) {
+                        None => ResolveResult::Success(name, value),
+                        Some(violation) => {
+                            ResolveResult::Failed(name, format!("constraint violated: {}", violation))
+                        }
+                    }
+                }
+                other => other,
+            };
+
             match result {
                 ResolveResult::Success(_, value) => {
-                    println!("  {} {:<24} ({})", "✓".green(), var_name, source_display);
+                    if pretty {
+                        println!("  {} {:<24} ({})", "✓".green(), var_name, source_display);
+                    }
+                    let masked = var.sensitive && !opts.show_secrets;
+                    let shown_value = if var.sensitive {
+                        output::mask_value(&value, opts.show_secrets)
+                    } else {
+                        value.clone()
+                    };
+                    reports.push(PullVariableReport {
+                        name: var_name.clone(),
+                        source: source_display.to_string(),
+                        status: "success",
+                        reason: None,
+                        value: Some(shown_value),
+                        required: var.required,
+                        masked,
+                    });
                     resolved_vars.push((var_name.clone(), value));
                 }
                 ResolveResult::Skipped(_, reason) => {
-                    println!(
-                        "  {} {:<24} ({}) — {}",
-                        "⊘".yellow(),
-                        var_name,
-                        source_display,
-                        reason
-                    );
+                    if pretty {
+                        println!(
+                            "  {} {:<24} ({}) — {}",
+                            "⊘".yellow(),
+                            var_name,
+                            source_display,
+                            reason
+                        );
+                    }
                     warnings.push(format!(
                         "{} could not be resolved (required={})",
                         var_name, var.required
                     ));
+                    reports.push(PullVariableReport {
+                        name: var_name.clone(),
+                        source: source_display.to_string(),
+                        status: "skipped",
+                        reason: Some(reason),
+                        value: None,
+                        required: var.required,
+                        masked: false,
+                    });
                 }
                 ResolveResult::Failed(_, error) => {
-                    println!(
-                        "  {} {:<24} ({}) — {}",
-                        "✗".red(),
-                        var_name,
-                        source_display,
-                        error
-                    );
+                    if pretty {
+                        println!(
+                            "  {} {:<24} ({}) — {}",
+                            "✗".red(),
+                            var_name,
+                            source_display,
+                            error
+                        );
+                    }
                     warnings.push(format!(
                         "{} could not be resolved (required={})",
                         var_name, var.required
@@ -489,32 +960,57 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
                     if var.required {
                         failed_required += 1;
                     }
+                    reports.push(PullVariableReport {
+                        name: var_name.clone(),
+                        source: source_display.to_string(),
+                        status: "failed",
+                        reason: Some(error),
+                        value: None,
+                        required: var.required,
+                        masked: false,
+                    });
                 }
             }
         }
     }
 
-    println!();
+    if pretty {
+        println!();
+    }
 
-    // Write output file
-    if !resolved_vars.is_empty() {
+    // Write output file. In `--watch` mode, a cycle with a failed required
+    // variable never writes: the point of watch mode is staying alive
+    // through a schema mid-edit, so the previous cycle's `.env` is left in
+    // place instead of being overwritten with an incomplete one.
+    let held_back_by_watch = opts.watch && failed_required > 0;
+    let written = !resolved_vars.is_empty() && !held_back_by_watch;
+    if written {
         output::write_env_file(
             &dest_path,
             &opts.schema_path.to_string_lossy(),
             &opts.env_name,
             &resolved_vars,
         )?;
+        if pretty {
+            println!(
+                "Wrote {} variable{} to {}",
+                resolved_vars.len(),
+                if resolved_vars.len() == 1 { "" } else { "s" },
+                dest_path.display()
+            );
+        }
+    } else if pretty && held_back_by_watch {
         println!(
-            "Wrote {} variable{} to {}",
-            resolved_vars.len(),
-            if resolved_vars.len() == 1 { "" } else { "s" },
+            "{} required variable{} failed to resolve; keeping the previous {}.",
+            failed_required,
+            if failed_required == 1 { "" } else { "s" },
             dest_path.display()
         );
-    } else {
+    } else if pretty {
         println!("No variables resolved. Output file not written.");
     }
 
-    if !warnings.is_empty() {
+    if pretty && !warnings.is_empty() {
         println!(
             "{} warning{}: {}",
             warnings.len(),
@@ -523,6 +1019,11 @@ pub async fn run_pull(opts: PullOptions) -> Result<bool> {
         );
     }
 
+    if !pretty {
+        PullReport::new(reports, dest_path.to_string_lossy().to_string(), written).print()?;
+        return Ok(failed_required == 0);
+    }
+
     if failed_required > 0 {
         println!();
         println!("Exit code: 1");
@@ -24,7 +24,7 @@ fn resolve_output_path(output: Option<PathBuf>) -> PathBuf {
 }
 
 pub fn run_schema_print() -> Result<()> {
-    print!("{}", schema::JSON_SCHEMA);
+    print!("{}", schema::json_schema());
     Ok(())
 }
 
@@ -45,7 +45,7 @@ pub fn run_schema_export(opts: SchemaExportOptions) -> Result<()> {
         );
     }
 
-    fs::write(&dest_path, schema::JSON_SCHEMA)?;
+    fs::write(&dest_path, schema::json_schema())?;
 
     if !opts.quiet {
         println!("Wrote JSON Schema to {}", dest_path.display());
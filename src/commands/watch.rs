@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use super::pull::{run_pull_once, PullOptions};
+
+/// How long to wait after the first filesystem event before re-resolving, to
+/// collapse the burst of writes a single editor save often produces into one
+/// cycle.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watch the schema file (and any `extends` parents it composes from) for
+/// changes and re-run [`run_pull_once`] on each change, keeping the process
+/// alive until interrupted.
+///
+/// A failed cycle (invalid schema, missing required variable, ...) is
+/// reported but never turns into a non-zero result here: the whole point of
+/// watch mode is to stay alive through a schema mid-edit. `run_pull_once`
+/// refuses to write the destination for a cycle with a failed required
+/// variable while `opts.watch` is set, so the `.env` file from the last
+/// successful cycle is left in place instead of being overwritten with an
+/// incomplete one.
+pub async fn run_watch(opts: &PullOptions) -> Result<bool> {
+    let watch_paths = crate::schema::compose::schema_chain_paths(&opts.schema_path)
+        .unwrap_or_else(|_| vec![opts.schema_path.clone()]);
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let mut watcher: RecommendedWatcher = notify::recommended_watcher(move |res| {
+        let _ = tx.send(res);
+    })
+    .context("Failed to start schema file watcher")?;
+
+    for path in &watch_paths {
+        watcher
+            .watch(path, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch \"{}\" for changes", path.display()))?;
+    }
+
+    println!(
+        "\n{} Watching {} for changes. Press Ctrl-C to stop.",
+        "◌".cyan(),
+        watch_paths
+            .iter()
+            .map(|p| format!("\"{}\"", p.display()))
+            .collect::<Vec<String>>()
+            .join(", ")
+    );
+
+    let mut rx = rx;
+    loop {
+        rx = wait_for_change(rx).await?;
+
+        println!("\n{} Schema changed, re-pulling...", "↻".cyan());
+        if let Err(e) = run_pull_once(opts).await {
+            eprintln!("{} {:#}", "✗".red(), e);
+        }
+    }
+}
+
+/// Block (off the async executor) until at least one event arrives, then
+/// drain any further events that show up within [`DEBOUNCE`] so a burst of
+/// writes collapses into a single wakeup. Returns the receiver back to the
+/// caller so it can be reused on the next iteration.
+async fn wait_for_change(rx: Receiver<notify::Result<Event>>) -> Result<Receiver<notify::Result<Event>>> {
+    tokio::task::spawn_blocking(move || -> Result<Receiver<notify::Result<Event>>> {
+        rx.recv()
+            .context("Schema file watcher channel closed unexpectedly")??;
+
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(event) => {
+                    event?;
+                }
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        Ok(rx)
+    })
+    .await
+    .context("Schema file watcher task panicked")?
+}
@@ -1,10 +1,12 @@
+mod chooser;
 mod commands;
 mod output;
 mod resolver;
 mod schema;
+mod suggest;
 mod template;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use std::path::PathBuf;
 use std::process;
 
@@ -31,9 +33,15 @@ enum Commands {
         #[arg(short, long)]
         schema: Option<PathBuf>,
 
-        /// Target environment (defaults to "local")
-        #[arg(short, long, default_value = "local")]
-        env: String,
+        /// Target environment. If omitted on a TTY, launches an interactive
+        /// chooser; otherwise defaults to "local".
+        #[arg(short, long)]
+        env: Option<String>,
+
+        /// External chooser to pipe environment names into when --env is
+        /// omitted (default: fzf, or $ENVGEN_CHOOSER)
+        #[arg(long)]
+        chooser: Option<String>,
 
         /// Print what would be written without executing anything
         #[arg(short = 'n', long)]
@@ -58,6 +66,27 @@ enum Commands {
         /// Timeout in seconds for source commands (default: 30)
         #[arg(long, default_value = "30")]
         timeout: u64,
+
+        /// Maximum number of command sources to run concurrently (default:
+        /// available parallelism)
+        #[arg(short, long)]
+        jobs: Option<usize>,
+
+        /// Keep running and re-pull whenever the schema file changes
+        #[arg(short, long)]
+        watch: bool,
+
+        /// Output format: pretty (default) or json
+        #[arg(long, default_value = "pretty")]
+        format: String,
+
+        /// Refuse every command source outright; only static/manual/env resolve
+        #[arg(long)]
+        deny_commands: bool,
+
+        /// Assume yes to command source confirmation prompts (for CI)
+        #[arg(short, long)]
+        yes: bool,
     },
 
     /// Create a sample schema file
@@ -73,6 +102,11 @@ enum Commands {
         /// Suppress success output
         #[arg(short, long)]
         quiet: bool,
+
+        /// Schema format: yaml, toml, or json (default: detected from
+        /// --output's extension, falling back to yaml)
+        #[arg(long)]
+        format: Option<String>,
     },
 
     /// Validate a schema file for correctness
@@ -97,6 +131,53 @@ enum Commands {
         format: String,
     },
 
+    /// Resolve a single variable and print its raw value to stdout
+    Get {
+        /// Path to schema YAML file
+        #[arg(short, long)]
+        schema: Option<PathBuf>,
+
+        /// Target environment (defaults to "local")
+        #[arg(short, long, default_value = "local")]
+        env: String,
+
+        /// Timeout in seconds for source commands (default: 30)
+        #[arg(long, default_value = "30")]
+        timeout: u64,
+
+        /// Print where the value would come from instead of resolving it
+        #[arg(long)]
+        explain: bool,
+
+        /// Refuse a command source outright if the variable uses one
+        #[arg(long)]
+        deny_commands: bool,
+
+        /// Assume yes to the command source confirmation prompt (for CI)
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Name of the variable to resolve
+        var_name: String,
+    },
+
+    /// Print a shell completion script to stdout
+    Completions {
+        /// Shell to generate completions for
+        shell: clap_complete::Shell,
+    },
+
+    /// Hidden helper used by shell completion scripts to list dynamic values
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// What to list: "environments" or "sources"
+        kind: String,
+
+        /// Path to schema YAML file
+        #[arg(short, long)]
+        schema: Option<PathBuf>,
+    },
+
     /// Export the embedded JSON Schema used to validate envgen YAML schemas
     Schema {
         /// Output path (file or directory)
@@ -115,6 +196,29 @@ enum Commands {
         #[arg(short, long)]
         quiet: bool,
     },
+
+    /// Generate typed bindings for a schema's variables
+    Codegen {
+        /// Path to schema YAML file
+        #[arg(short, long)]
+        schema: Option<PathBuf>,
+
+        /// Output language: "rust", "typescript", or "env-example"
+        #[arg(short, long)]
+        lang: String,
+
+        /// Output path (file or directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Overwrite the destination file if it already exists
+        #[arg(short, long)]
+        force: bool,
+
+        /// Suppress success output
+        #[arg(short, long)]
+        quiet: bool,
+    },
 }
 
 fn resolve_schema_path(global: &Option<PathBuf>, local: &Option<PathBuf>) -> PathBuf {
@@ -134,11 +238,21 @@ async fn main() {
             ref output,
             force,
             quiet,
+            ref format,
         } => {
+            let format = match format.as_deref().map(schema::parser::SchemaFormat::from_str) {
+                Some(Ok(f)) => Some(f),
+                Some(Err(e)) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+                None => None,
+            };
             let opts = commands::init::InitOptions {
                 output_path: output.clone(),
                 force,
                 quiet,
+                format,
             };
             match commands::init::run_init(opts) {
                 Ok(()) => 0,
@@ -183,23 +297,65 @@ async fn main() {
         Commands::Pull {
             ref schema,
             ref env,
+            ref chooser,
             dry_run,
             unmask,
             force,
             non_interactive,
             ref output,
             timeout,
+            jobs,
+            watch,
+            ref format,
+            deny_commands,
+            yes,
         } => {
             let schema_path = resolve_schema_path(&cli.schema, schema);
+
+            // No --env given: try an interactive chooser before falling
+            // back to the old implicit "local" default. The schema is
+            // re-loaded (and re-validated) inside run_pull regardless, so a
+            // failure here just skips straight to that default and lets
+            // run_pull report the real error.
+            let env_name = match env.clone() {
+                Some(name) => name,
+                None => {
+                    match schema::validation::load_and_validate_schema_file(&schema_path) {
+                        Ok(schema::validation::SchemaValidation::Valid(parsed_schema)) => {
+                            let available = parsed_schema.environment_names();
+                            let chooser_bin = chooser::resolve_chooser(chooser.as_deref());
+                            match chooser::choose(&chooser_bin, available.iter().map(String::as_str))
+                            {
+                                Some(choice) => choice,
+                                None => "local".to_string(),
+                            }
+                        }
+                        _ => "local".to_string(),
+                    }
+                }
+            };
+
+            let pull_format = match commands::pull::PullFormat::from_str(format) {
+                Ok(f) => f,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
             let opts = commands::pull::PullOptions {
                 schema_path,
-                env_name: env.clone(),
+                env_name,
                 dry_run,
                 unmask,
                 force,
                 non_interactive,
                 output_path: output.clone(),
                 timeout,
+                max_parallel: jobs,
+                watch,
+                format: pull_format,
+                deny_commands,
+                assume_yes: yes,
             };
             match commands::pull::run_pull(opts).await {
                 Ok(true) => 0,
@@ -210,6 +366,52 @@ async fn main() {
                 }
             }
         }
+        Commands::Get {
+            ref schema,
+            ref env,
+            timeout,
+            explain,
+            deny_commands,
+            yes,
+            ref var_name,
+        } => {
+            let schema_path = resolve_schema_path(&cli.schema, schema);
+            let opts = commands::get::GetOptions {
+                schema_path,
+                env_name: env.clone(),
+                var_name: var_name.clone(),
+                source_timeout: timeout,
+                explain,
+                deny_commands,
+                assume_yes: yes,
+            };
+            match commands::get::run_get(opts).await {
+                Ok(true) => 0,
+                Ok(false) => 1,
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    1
+                }
+            }
+        }
+        Commands::Completions { shell } => match commands::completions::run_completions(Cli::command(), shell)
+        {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("Error: {:#}", e);
+                1
+            }
+        },
+        Commands::Complete { ref kind, ref schema } => {
+            let schema_path = schema.clone().or_else(|| cli.schema.clone());
+            match commands::completions::run_complete(kind, schema_path) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    1
+                }
+            }
+        }
         Commands::Schema {
             ref output,
             stdout,
@@ -239,6 +441,36 @@ async fn main() {
                 }
             }
         }
+        Commands::Codegen {
+            ref schema,
+            ref lang,
+            ref output,
+            force,
+            quiet,
+        } => {
+            let schema_path = resolve_schema_path(&cli.schema, schema);
+            let codegen_lang = match commands::codegen::CodegenLang::from_str(lang) {
+                Ok(l) => l,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    process::exit(1);
+                }
+            };
+            let opts = commands::codegen::CodegenOptions {
+                schema_path,
+                lang: codegen_lang,
+                output_path: output.clone(),
+                force,
+                quiet,
+            };
+            match commands::codegen::run_codegen(opts) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("Error: {:#}", e);
+                    1
+                }
+            }
+        }
     };
 
     process::exit(exit_code);
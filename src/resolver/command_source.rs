@@ -1,11 +1,21 @@
 use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
 use std::process::Stdio;
 use std::time::Duration;
 use tokio::io::AsyncReadExt;
-use tokio::process::Command;
+use tokio::process::{Child, Command};
 
 use crate::template;
 
+#[cfg(windows)]
+use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+#[cfg(windows)]
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+    SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+    JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+};
+
 /// Result of executing a source command.
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -15,18 +25,56 @@ pub struct CommandResult {
 }
 
 /// Build the resolved command string from a source command template.
+/// `resolved_vars` supplies any other variables' already-resolved values,
+/// for `{var:name}` cross-references (see [`crate::schema::dependency`]).
+/// Only static/env-sourced dependencies are guaranteed to be present: command
+/// sources run later in a parallel phase, so a `{var:name}` reference to
+/// another command-sourced variable fails with an unresolved-placeholder
+/// error rather than silently resolving to an empty string.
 pub fn build_command(
     source_command_template: &str,
     var_name: &str,
     source_key: Option<&str>,
     env_name: &str,
     env_config: &std::collections::BTreeMap<String, String>,
+    resolved_vars: &HashMap<String, String>,
 ) -> Result<String> {
-    let key = source_key.unwrap_or(var_name);
-    let ctx = template::build_context(env_name, env_config, key);
+    let ctx = build_template_context(var_name, source_key, env_name, env_config, resolved_vars);
     template::expand_template(source_command_template, &ctx)
 }
 
+/// Build the resolved stdin payload from a source's `stdin_template`, the
+/// same way [`build_command`] builds the command line. Returns `None` when
+/// the source has no `stdin_template`, so callers can tell "no stdin" apart
+/// from "stdin that expands to an empty string".
+pub fn build_stdin(
+    stdin_template: Option<&str>,
+    var_name: &str,
+    source_key: Option<&str>,
+    env_name: &str,
+    env_config: &std::collections::BTreeMap<String, String>,
+    resolved_vars: &HashMap<String, String>,
+) -> Result<Option<String>> {
+    let Some(template_str) = stdin_template else {
+        return Ok(None);
+    };
+    let ctx = build_template_context(var_name, source_key, env_name, env_config, resolved_vars);
+    template::expand_template(template_str, &ctx).map(Some)
+}
+
+fn build_template_context(
+    var_name: &str,
+    source_key: Option<&str>,
+    env_name: &str,
+    env_config: &std::collections::BTreeMap<String, String>,
+    resolved_vars: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let key = source_key.unwrap_or(var_name);
+    let mut ctx = template::build_context(env_name, env_config, key);
+    template::apply_resolved_variables(&mut ctx, resolved_vars);
+    ctx
+}
+
 #[cfg(unix)]
 fn configure_process_group(cmd: &mut Command) {
     unsafe {
@@ -41,9 +89,9 @@ fn configure_process_group(cmd: &mut Command) {
 }
 
 #[cfg(unix)]
-fn kill_process_group_by_pid(pid: u32) -> std::io::Result<()> {
+fn kill_process_group_by_pid(pid: u32, signal: libc::c_int) -> std::io::Result<()> {
     let pgid = -(pid as i32);
-    let rc = unsafe { libc::kill(pgid, libc::SIGKILL) };
+    let rc = unsafe { libc::kill(pgid, signal) };
     if rc == 0 {
         return Ok(());
     }
@@ -56,19 +104,198 @@ fn kill_process_group_by_pid(pid: u32) -> std::io::Result<()> {
     Err(err)
 }
 
-/// Execute a source command and return the trimmed stdout.
-pub async fn execute_command(command: &str, timeout_secs: u64) -> Result<CommandResult> {
+/// A Job Object configured to tear down every process assigned to it as
+/// soon as the last handle to the job closes (`JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE`).
+/// This is the Windows equivalent of the Unix process group: assigning the
+/// spawned child to it lets us kill the whole descendant tree, not just the
+/// direct child, via [`terminate_job_object`] or by dropping the handle.
+#[cfg(windows)]
+struct JobHandle(HANDLE);
+
+#[cfg(windows)]
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.0);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn create_kill_on_close_job() -> std::io::Result<JobHandle> {
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut info: JOBOBJECT_EXTENDED_LIMIT_INFORMATION = std::mem::zeroed();
+        info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        let rc = SetInformationJobObject(
+            job,
+            JobObjectExtendedLimitInformation,
+            &info as *const _ as *const _,
+            std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+        );
+        if rc == 0 {
+            let err = std::io::Error::last_os_error();
+            CloseHandle(job);
+            return Err(err);
+        }
+
+        Ok(JobHandle(job))
+    }
+}
+
+/// Creates a kill-on-close job and assigns `child` to it. There's an
+/// unavoidable brief window between spawn and this call in which a very
+/// short-lived child could fork a grandchild outside the job; in practice
+/// source commands run for the lifetime of the whole `execute_command` call,
+/// so this is the same best-effort guarantee `configure_process_group` makes
+/// on Unix (where the equivalent race is closed instead via `pre_exec`,
+/// which `tokio::process::Command` has no Windows analog for).
+#[cfg(windows)]
+fn configure_job_object(child: &Child) -> std::io::Result<JobHandle> {
+    use std::os::windows::io::AsRawHandle;
+
+    let job = create_kill_on_close_job()?;
+    let handle = child.as_raw_handle() as HANDLE;
+    let rc = unsafe { AssignProcessToJobObject(job.0, handle) };
+    if rc == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(job)
+}
+
+#[cfg(windows)]
+fn terminate_job_object(job: &JobHandle) -> std::io::Result<()> {
+    let rc = unsafe { windows_sys::Win32::System::JobObjects::TerminateJobObject(job.0, 1) };
+    if rc == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// How a timed-out command's process group is terminated, modeled on
+/// Turborepo's process manager.
+#[derive(Debug, Clone, Copy)]
+pub enum ShutdownStyle {
+    /// Send `SIGTERM` to the process group first, giving it `Duration` to
+    /// exit on its own before escalating to `SIGKILL`.
+    Graceful(Duration),
+    /// Send `SIGKILL` to the process group immediately.
+    Forceful,
+}
+
+impl Default for ShutdownStyle {
+    /// A short grace period, matching Turborepo's default.
+    fn default() -> Self {
+        ShutdownStyle::Graceful(Duration::from_millis(500))
+    }
+}
+
+/// RAII guard recording how long a source command took and whether it
+/// finished cleanly, modeled on pict-rs's `MetricsGuard`. Created at spawn
+/// time and `disarm`ed right before a successful return; if dropped while
+/// still armed (a timeout, a non-zero exit, or an early `?`/`bail!`), it
+/// reports `completed = false` instead. `label` identifies the source
+/// (e.g. its name in `sources`) rather than the interpolated command
+/// string, so a secret value baked into a command's arguments is never
+/// attached to a metric.
+#[cfg(feature = "metrics")]
+struct CommandMetricsGuard {
+    label: String,
+    start: std::time::Instant,
+    armed: bool,
+}
+
+#[cfg(feature = "metrics")]
+impl CommandMetricsGuard {
+    fn new(label: &str) -> Self {
+        metrics::counter!("envgen_command_started_total", "command" => label.to_string())
+            .increment(1);
+        Self {
+            label: label.to_string(),
+            start: std::time::Instant::now(),
+            armed: true,
+        }
+    }
+
+    fn disarm(&mut self) {
+        self.armed = false;
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for CommandMetricsGuard {
+    fn drop(&mut self) {
+        let completed = !self.armed;
+        metrics::histogram!(
+            "envgen_command_duration_seconds",
+            "command" => self.label.clone(),
+            "completed" => completed.to_string(),
+        )
+        .record(self.start.elapsed().as_secs_f64());
+        metrics::counter!(
+            "envgen_command_finished_total",
+            "command" => self.label.clone(),
+            "completed" => completed.to_string(),
+        )
+        .increment(1);
+    }
+}
+
+/// No-op stand-in for [`CommandMetricsGuard`] when the `metrics` feature is
+/// disabled, so `execute_command` doesn't need to `#[cfg]` its call sites.
+#[cfg(not(feature = "metrics"))]
+struct CommandMetricsGuard;
+
+#[cfg(not(feature = "metrics"))]
+impl CommandMetricsGuard {
+    fn new(_label: &str) -> Self {
+        Self
+    }
+
+    fn disarm(&mut self) {}
+}
+
+/// Execute a source command and return the trimmed stdout. `label`
+/// identifies the command for metrics (see [`CommandMetricsGuard`]) and
+/// should be a stable, secret-free name such as the source's key in
+/// `sources`, not the interpolated command string itself.
+///
+/// `stdin`, when present, is piped to the child's stdin instead of being
+/// substituted into `command` (see [`build_stdin`]), so a secret or lookup
+/// key it carries never appears on argv, in `ps` output, or in shell
+/// history.
+pub async fn execute_command(
+    command: &str,
+    label: &str,
+    stdin: Option<&str>,
+    timeout_secs: u64,
+    shutdown: ShutdownStyle,
+) -> Result<CommandResult> {
     enum WaitOutcome {
         Completed(std::io::Result<std::process::ExitStatus>),
         TimedOut,
     }
 
+    // `shutdown`'s graceful grace period is unix-only: Job Objects (the
+    // Windows tree-kill mechanism, see `configure_job_object`) have no
+    // SIGTERM equivalent to grace-period against.
+    #[cfg(not(unix))]
+    let _ = shutdown;
+
     let timeout = Duration::from_secs(timeout_secs);
 
     let mut cmd = Command::new("sh");
     cmd.arg("-c")
         .arg(command)
-        .stdin(Stdio::null())
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
         .kill_on_drop(true);
@@ -77,7 +304,28 @@ pub async fn execute_command(command: &str, timeout_secs: u64) -> Result<Command
     configure_process_group(&mut cmd);
 
     let mut child = cmd.spawn().context("Failed to execute command")?;
+    let mut metrics_guard = CommandMetricsGuard::new(label);
+
+    #[cfg(unix)]
     let pid = child.id();
+    #[cfg(windows)]
+    let job = configure_job_object(&child).context("Failed to configure job object")?;
+
+    // Written concurrently with the stdout/stderr readers below so a child
+    // that doesn't read all of stdin before producing output (or that
+    // produces enough output to fill its stdout/stderr pipe before reading
+    // stdin) can't deadlock against us.
+    let stdin_task = stdin.map(|payload| {
+        let mut stdin_handle = child.stdin.take().expect("stdin was piped");
+        let payload = payload.to_string();
+        tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            let _ = stdin_handle.write_all(payload.as_bytes()).await;
+            // Close stdin regardless of write outcome, so the child sees
+            // EOF instead of hanging waiting for more input.
+            drop(stdin_handle);
+        })
+    });
 
     let mut stdout = child
         .stdout
@@ -100,6 +348,7 @@ pub async fn execute_command(command: &str, timeout_secs: u64) -> Result<Command
     });
 
     let mut timed_out = false;
+    let mut terminated_cleanly = false;
     let mut wait_error: Option<std::io::Error> = None;
     let mut exit_status: Option<std::process::ExitStatus> = None;
 
@@ -114,30 +363,62 @@ pub async fn execute_command(command: &str, timeout_secs: u64) -> Result<Command
         WaitOutcome::TimedOut => {
             timed_out = true;
 
+            #[cfg(unix)]
             if let Some(pid) = pid {
-                #[cfg(unix)]
-                {
-                    let _ = kill_process_group_by_pid(pid);
+                match shutdown {
+                    ShutdownStyle::Graceful(grace_period) => {
+                        let _ = kill_process_group_by_pid(pid, libc::SIGTERM);
+                        terminated_cleanly = tokio::select! {
+                            res = child.wait() => { let _ = res; true }
+                            _ = tokio::time::sleep(grace_period) => false,
+                        };
+                        if !terminated_cleanly {
+                            let _ = kill_process_group_by_pid(pid, libc::SIGKILL);
+                        }
+                    }
+                    ShutdownStyle::Forceful => {
+                        let _ = kill_process_group_by_pid(pid, libc::SIGKILL);
+                    }
                 }
             }
 
+            // Job Objects have no SIGTERM equivalent, so `shutdown` is moot
+            // here: terminate the whole tree outright either way.
+            #[cfg(windows)]
+            let _ = terminate_job_object(&job);
+
             let _ = child.kill().await;
             let _ = child.wait().await;
         }
     }
 
-    let stdout_bytes = stdout_task
-        .await
-        .context("Failed to join stdout reader task")??;
-    let stderr_bytes = stderr_task
-        .await
-        .context("Failed to join stderr reader task")??;
+    let (stdout_result, stderr_result, stdin_result) = tokio::join!(
+        stdout_task,
+        stderr_task,
+        async {
+            match stdin_task {
+                Some(task) => task.await,
+                None => Ok(()),
+            }
+        }
+    );
+    let stdout_bytes = stdout_result.context("Failed to join stdout reader task")??;
+    let stderr_bytes = stderr_result.context("Failed to join stderr reader task")??;
+    // A failed stdin write (e.g. the child exited before reading all of
+    // it) doesn't invalidate a command that otherwise ran to completion.
+    let _ = stdin_result;
 
     if let Some(e) = wait_error {
         bail!("Failed to execute command: {}", e);
     }
 
     if timed_out {
+        if terminated_cleanly {
+            bail!(
+                "Command timed out after {} seconds (terminated gracefully)",
+                timeout_secs
+            );
+        }
         bail!("Command timed out after {} seconds", timeout_secs);
     }
 
@@ -151,6 +432,7 @@ pub async fn execute_command(command: &str, timeout_secs: u64) -> Result<Command
         );
     }
     let stdout = String::from_utf8_lossy(&stdout_bytes).trim().to_string();
+    metrics_guard.disarm();
     Ok(CommandResult {
         value: stdout,
         stderr,
@@ -169,7 +451,15 @@ mod tests {
         let mut env_config = BTreeMap::new();
         env_config.insert("firebase_project".to_string(), "my-proj".to_string());
 
-        let cmd = build_command(template, "MY_SECRET", None, "staging", &env_config).unwrap();
+        let cmd = build_command(
+            template,
+            "MY_SECRET",
+            None,
+            "staging",
+            &env_config,
+            &HashMap::new(),
+        )
+        .unwrap();
         assert_eq!(
             cmd,
             "firebase functions:secrets:access MY_SECRET --project my-proj"
@@ -187,26 +477,48 @@ mod tests {
             Some("GOOGLE_ID"),
             "local",
             &env_config,
+            &HashMap::new(),
         )
         .unwrap();
         assert_eq!(cmd, "echo GOOGLE_ID");
     }
 
+    #[test]
+    fn test_build_command_with_variable_reference() {
+        let template = "echo {var:BASE}-{key}";
+        let env_config = BTreeMap::new();
+        let mut resolved_vars = HashMap::new();
+        resolved_vars.insert("BASE".to_string(), "root".to_string());
+
+        let cmd = build_command(
+            template,
+            "DERIVED",
+            None,
+            "local",
+            &env_config,
+            &resolved_vars,
+        )
+        .unwrap();
+        assert_eq!(cmd, "echo root-DERIVED");
+    }
+
     #[tokio::test]
     async fn test_execute_command_success() {
-        let result = execute_command("echo hello", 30).await.unwrap();
+        let result = execute_command("echo hello", "test", None, 30, ShutdownStyle::default())
+            .await
+            .unwrap();
         assert_eq!(result.value, "hello");
     }
 
     #[tokio::test]
     async fn test_execute_command_failure() {
-        let result = execute_command("exit 1", 30).await;
+        let result = execute_command("exit 1", "test", None, 30, ShutdownStyle::default()).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_execute_command_timeout() {
-        let result = execute_command("sleep 10", 1).await;
+        let result = execute_command("sleep 10", "test", None, 1, ShutdownStyle::default()).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("timed out"));
     }
@@ -220,7 +532,7 @@ mod tests {
             side_effect_path.display()
         );
 
-        let result = execute_command(&cmd, 1).await;
+        let result = execute_command(&cmd, "test", None, 1, ShutdownStyle::default()).await;
         assert!(result.is_err());
 
         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -229,4 +541,81 @@ mod tests {
             "side effect should not run after a timeout"
         );
     }
+
+    #[tokio::test]
+    async fn test_execute_command_graceful_shutdown_does_not_escalate_to_sigkill() {
+        // Traps SIGTERM and exits immediately instead of the default
+        // terminate-on-signal behavior, so this only succeeds if the
+        // graceful path actually sends SIGTERM (rather than jumping straight
+        // to SIGKILL, which can't be trapped).
+        let cmd = "trap 'exit 0' TERM; sleep 10 & wait";
+
+        let result = execute_command(
+            cmd,
+            "test",
+            None,
+            1,
+            ShutdownStyle::Graceful(std::time::Duration::from_millis(500)),
+        )
+        .await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("terminated gracefully"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_command_forceful_shutdown_reports_plain_timeout() {
+        let result = execute_command("sleep 10", "test", None, 1, ShutdownStyle::Forceful).await;
+        assert!(result.is_err());
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("timed out"));
+        assert!(!message.contains("terminated gracefully"));
+    }
+
+    #[tokio::test]
+    async fn test_execute_command_pipes_stdin_to_child() {
+        let result = execute_command(
+            "cat",
+            "test",
+            Some("secret-payload"),
+            30,
+            ShutdownStyle::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.value, "secret-payload");
+    }
+
+    #[tokio::test]
+    async fn test_execute_command_without_stdin_does_not_block_on_read() {
+        // A command that tries to read stdin would hang forever if stdin
+        // were piped-but-never-written instead of closed outright.
+        let result = execute_command("cat; echo done", "test", None, 5, ShutdownStyle::default())
+            .await
+            .unwrap();
+        assert_eq!(result.value, "done");
+    }
+
+    #[test]
+    fn test_build_stdin_returns_none_without_template() {
+        let env_config = BTreeMap::new();
+        let stdin = build_stdin(None, "VAR", None, "local", &env_config, &HashMap::new()).unwrap();
+        assert!(stdin.is_none());
+    }
+
+    #[test]
+    fn test_build_stdin_expands_template() {
+        let mut env_config = BTreeMap::new();
+        env_config.insert("vault_path".to_string(), "secret/my-app".to_string());
+
+        let stdin = build_stdin(
+            Some("path={vault_path}"),
+            "VAR",
+            None,
+            "local",
+            &env_config,
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(stdin.as_deref(), Some("path=secret/my-app"));
+    }
 }
@@ -0,0 +1,56 @@
+use anyhow::{anyhow, Result};
+
+/// Where a `source: env` variable reads its value from. Abstracted behind a
+/// trait (rather than calling `std::env::var` directly at each call site) so
+/// tests can supply a fake environment instead of the real process one.
+pub trait EnvironmentProvider {
+    fn get(&self, key: &str) -> Option<String>;
+}
+
+/// Reads from the real process environment.
+pub struct ProcessEnvironment;
+
+impl EnvironmentProvider for ProcessEnvironment {
+    fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok()
+    }
+}
+
+/// Resolve a variable whose source is `env`: reads `key` (the effective
+/// per-environment key, falling back to the variable name) from `provider`.
+pub fn resolve_env(var_name: &str, key: &str, provider: &dyn EnvironmentProvider) -> Result<String> {
+    provider
+        .get(key)
+        .ok_or_else(|| anyhow!("{}: environment variable \"{}\" is not set", var_name, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct FakeEnvironment(HashMap<String, String>);
+
+    impl EnvironmentProvider for FakeEnvironment {
+        fn get(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+    }
+
+    #[test]
+    fn test_resolve_env_reads_configured_key() {
+        let mut vars = HashMap::new();
+        vars.insert("DEV_API_TOKEN".to_string(), "secret123".to_string());
+        let provider = FakeEnvironment(vars);
+
+        let result = resolve_env("API_TOKEN", "DEV_API_TOKEN", &provider).unwrap();
+        assert_eq!(result, "secret123");
+    }
+
+    #[test]
+    fn test_resolve_env_missing_key_errors() {
+        let provider = FakeEnvironment(HashMap::new());
+        let result = resolve_env("API_TOKEN", "API_TOKEN", &provider);
+        assert!(result.is_err());
+    }
+}
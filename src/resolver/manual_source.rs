@@ -1,5 +1,5 @@
 use anyhow::Result;
-use dialoguer::{Input, Password};
+use dialoguer::{Input, Password, Select};
 use std::collections::{BTreeMap, HashMap};
 
 use crate::template;
@@ -13,6 +13,16 @@ pub struct ManualResolveOptions<'a> {
     pub env_config: &'a BTreeMap<String, String>,
     pub sensitive: bool,
     pub non_interactive: bool,
+    /// Renders a selection menu of these values instead of a free-text
+    /// prompt. Mutually exclusive with `pattern` (enforced by
+    /// [`crate::schema::validator::validate_schema`]).
+    pub choices: Option<&'a [String]>,
+    /// Regex the entered value must match; re-prompts with the failure
+    /// message until satisfied.
+    pub pattern: Option<&'a str>,
+    /// Pre-filled/accept-on-enter answer, also returned in non-interactive
+    /// mode instead of skipping.
+    pub default: Option<&'a str>,
 }
 
 fn print_labeled_multiline(indent: &str, label: &str, value: &str) {
@@ -33,11 +43,29 @@ fn print_labeled_multiline(indent: &str, label: &str, value: &str) {
     }
 }
 
+/// Checks `value` against `pattern`, if any, returning the validation
+/// failure message on mismatch.
+fn check_pattern(pattern: Option<&str>, value: &str) -> Result<(), String> {
+    let Some(pattern) = pattern else {
+        return Ok(());
+    };
+    let re = regex::Regex::new(pattern)
+        .map_err(|e| format!("pattern \"{}\" is not a valid regex: {}", pattern, e))?;
+    if re.is_match(value) {
+        Ok(())
+    } else {
+        Err(format!("value does not match pattern \"{}\"", pattern))
+    }
+}
+
 /// Prompt the user for a manual variable value.
-/// Returns None if non-interactive mode is enabled.
+///
+/// Returns `opts.default` (or `None` if unset) without prompting when
+/// `opts.non_interactive` is set, so CI runs can proceed when a safe
+/// default is configured.
 pub fn resolve_manual(opts: ManualResolveOptions<'_>) -> Result<Option<String>> {
     if opts.non_interactive {
-        return Ok(None);
+        return Ok(opts.default.map(|d| d.to_string()));
     }
 
     // Build context for expanding template placeholders in instructions
@@ -54,11 +82,49 @@ pub fn resolve_manual(opts: ManualResolveOptions<'_>) -> Result<Option<String>>
 
     println!();
 
+    if let Some(choices) = opts.choices {
+        let prompt = format!("  Select a value for {}", opts.var_name);
+        let default_index = opts
+            .default
+            .and_then(|d| choices.iter().position(|c| c == d))
+            .unwrap_or(0);
+        let selection = Select::new()
+            .with_prompt(prompt)
+            .items(choices)
+            .default(default_index)
+            .interact()?;
+        return Ok(Some(choices[selection].clone()));
+    }
+
     let prompt = format!("  Enter value for {}", opts.var_name);
     let value: String = if opts.sensitive {
-        Password::new().with_prompt(prompt).interact()?
+        loop {
+            let candidate = Password::new()
+                .with_prompt(prompt.clone())
+                .allow_empty_password(opts.default.is_some())
+                .interact()?;
+            let candidate = match opts.default {
+                Some(default) if candidate.is_empty() => default.to_string(),
+                _ => candidate,
+            };
+            match check_pattern(opts.pattern, &candidate) {
+                Ok(()) => break candidate,
+                Err(message) => println!("  {}", message),
+            }
+        }
     } else {
-        Input::new().with_prompt(prompt).interact_text()?
+        let mut input = Input::new();
+        input = input.with_prompt(prompt);
+        if let Some(default) = opts.default {
+            input = input.default(default.to_string());
+        }
+        if let Some(pattern) = opts.pattern {
+            let pattern = pattern.to_string();
+            input = input.validate_with(move |v: &String| -> Result<(), String> {
+                check_pattern(Some(&pattern), v)
+            });
+        }
+        input.interact_text()?
     };
 
     Ok(Some(value))
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::resolver::command_source;
+use crate::schema::types::{Schema, Variable};
+
+/// Where a variable's resolved value for a given environment comes from,
+/// plus whatever identifying details that layer carries (the key it was
+/// looked up under, a human label/url, or the fully expanded command).
+///
+/// Built from the same `resolver_for_env`/`effective_source_for_env`
+/// lookups the resolve path already uses, so `--explain` can never disagree
+/// with what actually got resolved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Provenance {
+    /// An inline value from the variable's (or its per-environment
+    /// resolver's) `values` map, which is keyed by environment name; `key`
+    /// is the variable's effective source key for that environment.
+    Static { key: String },
+
+    /// A value the user is expected to supply via an interactive prompt.
+    Manual,
+
+    /// A value read from the process environment under `key`.
+    Env { key: String },
+
+    /// A value produced by running a source command.
+    Command {
+        source: String,
+        label: Option<String>,
+        url: Option<String>,
+        command: String,
+    },
+}
+
+/// Describes where `var_name` would get its value in `env_name`, without
+/// actually resolving it.
+pub fn describe(
+    schema: &Schema,
+    var_name: &str,
+    var: &Variable,
+    env_name: &str,
+    env_config: &BTreeMap<String, String>,
+) -> Result<Provenance> {
+    let source = var
+        .effective_source_for_env(env_name, env_config)
+        .ok_or_else(|| {
+            anyhow!(
+                "\"{}\" has no source configured for environment \"{}\".",
+                var_name,
+                env_name
+            )
+        })?;
+    let key = var.effective_key_for_env(var_name, env_name, env_config);
+
+    match source {
+        "static" => Ok(Provenance::Static { key }),
+        "manual" => Ok(Provenance::Manual),
+        "env" => Ok(Provenance::Env { key }),
+        _ => {
+            let resolver = var.resolver_for_env(env_name, env_config);
+            let src = schema.sources.get(source).ok_or_else(|| {
+                anyhow!("Source \"{}\" is not defined in sources.", source)
+            })?;
+            let label = resolver
+                .and_then(|r| r.label.clone())
+                .or_else(|| src.label.clone());
+            let url = resolver
+                .and_then(|r| r.url.clone())
+                .or_else(|| src.url.clone());
+            // `--explain` doesn't resolve other variables' values, so a
+            // `{var:X}` reference here surfaces the same "unresolved
+            // template placeholder" error as a missing `env_config` key
+            // would, rather than silently showing a blank.
+            let command = command_source::build_command(
+                &src.command,
+                var_name,
+                Some(&key),
+                env_name,
+                env_config,
+                &std::collections::HashMap::new(),
+            )?;
+
+            Ok(Provenance::Command {
+                source: source.to_string(),
+                label,
+                url,
+                command,
+            })
+        }
+    }
+}
+
+impl Provenance {
+    /// A single-line, human-readable rendering for `--explain` output and
+    /// docs annotations.
+    pub fn describe_line(&self) -> String {
+        match self {
+            Provenance::Static { key } => format!("static (key: {})", key),
+            Provenance::Manual => "manual (interactive prompt)".to_string(),
+            Provenance::Env { key } => format!("env (${})", key),
+            Provenance::Command {
+                source,
+                label,
+                url,
+                command,
+            } => {
+                let mut line = format!("{} (command: {})", source, command);
+                if let Some(label) = label {
+                    line.push_str(&format!(", label: {}", label));
+                }
+                if let Some(url) = url {
+                    line.push_str(&format!(", url: {}", url));
+                }
+                line
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::types::{EnvironmentConfig, Metadata, Source};
+
+    fn schema_fixture() -> Schema {
+        let mut environments = BTreeMap::new();
+        environments.insert(
+            "local".to_string(),
+            EnvironmentConfig {
+                extends: None,
+                config: BTreeMap::new(),
+            },
+        );
+
+        let mut sources = BTreeMap::new();
+        sources.insert(
+            "vault".to_string(),
+            Source {
+                command: "vault read -field=value secret/{key}".to_string(),
+                stdin_template: None,
+                label: Some("HashiCorp Vault".to_string()),
+                url: Some("https://vault.example.com".to_string()),
+                description: None,
+            },
+        );
+
+        let mut destination = BTreeMap::new();
+        destination.insert("local".to_string(), ".env".to_string());
+
+        Schema {
+            schema_version: "1".to_string(),
+            metadata: Metadata {
+                description: "test".to_string(),
+                destination,
+                command_allowlist: Vec::new(),
+            },
+            environments,
+            sources,
+            variables: BTreeMap::new(),
+            extends: None,
+        }
+    }
+
+    fn variable(source: &str) -> Variable {
+        Variable {
+            description: "A variable".to_string(),
+            sensitive: false,
+            source: Some(source.to_string()),
+            source_key: None,
+            source_instructions: None,
+            choices: None,
+            pattern: None,
+            default: None,
+            environments: None,
+            values: Some(BTreeMap::from([("local".to_string(), "hi".to_string())])),
+            resolvers: None,
+            required: true,
+            constraints: None,
+            notes: None,
+        }
+    }
+
+    #[test]
+    fn test_describe_static_source() {
+        let schema = schema_fixture();
+        let var = variable("static");
+        let provenance = describe(&schema, "MY_VAR", &var, "local", &BTreeMap::new()).unwrap();
+        assert_eq!(
+            provenance,
+            Provenance::Static {
+                key: "MY_VAR".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_describe_command_source_includes_label_and_expanded_command() {
+        let schema = schema_fixture();
+        let var = variable("vault");
+        let provenance =
+            describe(&schema, "MY_VAR", &var, "local", &BTreeMap::new()).unwrap();
+        match provenance {
+            Provenance::Command {
+                source,
+                label,
+                url,
+                command,
+            } => {
+                assert_eq!(source, "vault");
+                assert_eq!(label.as_deref(), Some("HashiCorp Vault"));
+                assert_eq!(url.as_deref(), Some("https://vault.example.com"));
+                assert_eq!(command, "vault read -field=value secret/MY_VAR");
+            }
+            other => panic!("expected Command provenance, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_describe_undefined_source_errors() {
+        let schema = schema_fixture();
+        let var = variable("ghost");
+        let result = describe(&schema, "MY_VAR", &var, "local", &BTreeMap::new());
+        assert!(result.is_err());
+    }
+}
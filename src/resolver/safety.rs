@@ -0,0 +1,122 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+
+/// A compiled form of `metadata.command_allowlist`: regex patterns matched
+/// against a command source's fully expanded command string (i.e. after
+/// `{key}`, `{environment}`, and environment-config placeholders have all
+/// been substituted), so users confirm exactly what will run.
+#[derive(Debug, Clone, Default)]
+pub struct CommandAllowlist {
+    patterns: Vec<Regex>,
+}
+
+impl CommandAllowlist {
+    pub fn compile(patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|p| {
+                Regex::new(p)
+                    .map_err(|e| anyhow!("invalid command_allowlist pattern \"{}\": {}", p, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(CommandAllowlist { patterns })
+    }
+
+    /// Returns true if `command` matches any pattern in this allowlist.
+    pub fn allows(&self, command: &str) -> bool {
+        self.patterns.iter().any(|re| re.is_match(command))
+    }
+}
+
+/// Outcome of [`gate_command`].
+pub enum CommandGate {
+    /// The command may run.
+    Allowed,
+    /// The command must not run; `reason` explains why (refused outright by
+    /// `--deny-commands`, or declined at the confirmation prompt).
+    Denied(String),
+}
+
+/// Decides whether `command` (the fully expanded command string for
+/// `var_name`) may run.
+///
+/// `deny_commands` refuses every command source outright. Otherwise, a
+/// command matching `allowlist` runs unattended; anything else needs
+/// interactive confirmation unless `assume_yes` bypasses the prompt (for
+/// `--yes` / non-interactive contexts like CI).
+pub fn gate_command(
+    var_name: &str,
+    command: &str,
+    allowlist: &CommandAllowlist,
+    deny_commands: bool,
+    assume_yes: bool,
+) -> Result<CommandGate> {
+    if deny_commands {
+        return Ok(CommandGate::Denied(format!(
+            "\"{}\" uses a command source, which is refused by --deny-commands.",
+            var_name
+        )));
+    }
+
+    if assume_yes || allowlist.allows(command) {
+        return Ok(CommandGate::Allowed);
+    }
+
+    let confirmed = dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "Run the following command to resolve \"{}\"?\n    {}",
+            var_name, command
+        ))
+        .default(false)
+        .interact()?;
+
+    if confirmed {
+        Ok(CommandGate::Allowed)
+    } else {
+        Ok(CommandGate::Denied(format!(
+            "\"{}\" was not confirmed; its command source was skipped.",
+            var_name
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist_matches_fully_expanded_command() {
+        let allowlist =
+            CommandAllowlist::compile(&["^aws secretsmanager get-secret-value .*".to_string()])
+                .unwrap();
+        assert!(allowlist.allows("aws secretsmanager get-secret-value --secret-id API_TOKEN"));
+        assert!(!allowlist.allows("rm -rf /"));
+    }
+
+    #[test]
+    fn test_invalid_pattern_errors() {
+        let result = CommandAllowlist::compile(&["(unterminated".to_string()]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_deny_commands_refuses_regardless_of_allowlist() {
+        let allowlist = CommandAllowlist::compile(&[".*".to_string()]).unwrap();
+        let gate = gate_command("MY_VAR", "echo hi", &allowlist, true, true).unwrap();
+        assert!(matches!(gate, CommandGate::Denied(_)));
+    }
+
+    #[test]
+    fn test_allowlisted_command_does_not_prompt() {
+        let allowlist = CommandAllowlist::compile(&["^echo hi$".to_string()]).unwrap();
+        let gate = gate_command("MY_VAR", "echo hi", &allowlist, false, false).unwrap();
+        assert!(matches!(gate, CommandGate::Allowed));
+    }
+
+    #[test]
+    fn test_assume_yes_bypasses_prompt() {
+        let allowlist = CommandAllowlist::default();
+        let gate = gate_command("MY_VAR", "echo hi", &allowlist, false, true).unwrap();
+        assert!(matches!(gate, CommandGate::Allowed));
+    }
+}
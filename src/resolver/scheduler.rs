@@ -0,0 +1,164 @@
+//! Bounded-concurrency execution of command-source jobs. `pull` can have
+//! dozens of variables each shelling out to a cloud CLI; spawning all of
+//! them at once saturates cores and file descriptors, so this caps
+//! concurrently-running children via a [`tokio::sync::Semaphore`] sized by
+//! `--jobs` (or [`default_parallelism`]), starting the next job as soon as a
+//! slot frees up rather than running fixed-size batches.
+
+use std::sync::Arc;
+
+use tokio::sync::Semaphore;
+
+use super::command_source::{self, CommandResult, ShutdownStyle};
+
+/// One command source to resolve. `label` identifies the source for
+/// metrics/errors (see [`command_source::execute_command`]) and is
+/// independent of `var_name`, since several variables can share a source.
+pub struct CommandJob {
+    pub var_name: String,
+    pub label: String,
+    pub command: String,
+    /// Piped to the command's stdin instead of substituted into `command`
+    /// (see [`command_source::build_stdin`]).
+    pub stdin: Option<String>,
+    pub timeout_secs: u64,
+    pub shutdown: ShutdownStyle,
+}
+
+/// The outcome of running a batch of [`CommandJob`]s: each job's own
+/// `Result`, in the same order the jobs were given (regardless of which
+/// child happened to finish first), keyed by `var_name`.
+pub struct SchedulerReport {
+    pub results: Vec<(String, anyhow::Result<CommandResult>)>,
+}
+
+/// Parallelism to use when no explicit `--jobs`/config value is given: the
+/// number of available CPUs.
+pub fn default_parallelism() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Runs `jobs` concurrently, at most `max_parallel` children at a time,
+/// collecting every result (success or failure) rather than aborting the
+/// batch on the first error. A job whose task panics is reported as a
+/// failure for that `var_name` rather than propagating the panic.
+pub async fn run_command_jobs(jobs: Vec<CommandJob>, max_parallel: usize) -> SchedulerReport {
+    let semaphore = Arc::new(Semaphore::new(max_parallel.max(1)));
+
+    let mut handles = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        let semaphore = Arc::clone(&semaphore);
+        let var_name = job.var_name.clone();
+        handles.push((
+            var_name,
+            tokio::spawn(async move {
+                let _permit = semaphore
+                    .acquire()
+                    .await
+                    .expect("scheduler semaphore is never closed");
+                command_source::execute_command(
+                    &job.command,
+                    &job.label,
+                    job.stdin.as_deref(),
+                    job.timeout_secs,
+                    job.shutdown,
+                )
+                .await
+            }),
+        ));
+    }
+
+    let mut results = Vec::with_capacity(handles.len());
+    for (var_name, handle) in handles {
+        let result = match handle.await {
+            Ok(result) => result,
+            Err(e) => Err(anyhow::anyhow!("command source task panicked: {}", e)),
+        };
+        results.push((var_name, result));
+    }
+
+    SchedulerReport { results }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sleep_job(var_name: &str) -> CommandJob {
+        CommandJob {
+            var_name: var_name.to_string(),
+            label: "test".to_string(),
+            command: "sleep 1".to_string(),
+            stdin: None,
+            timeout_secs: 30,
+            shutdown: ShutdownStyle::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jobs_run_concurrently_not_sequentially() {
+        // Four 1s sleeps bounded to 4-way concurrency should overlap and
+        // finish in well under 4s; run sequentially they'd take ~4s.
+        let jobs = vec![
+            sleep_job("A"),
+            sleep_job("B"),
+            sleep_job("C"),
+            sleep_job("D"),
+        ];
+
+        let start = std::time::Instant::now();
+        let report = run_command_jobs(jobs, 4).await;
+        let elapsed = start.elapsed();
+
+        assert!(
+            report.results.iter().all(|(_, r)| r.is_ok()),
+            "all jobs should succeed: {:?}",
+            report.results.iter().map(|(n, r)| (n, r.is_ok())).collect::<Vec<_>>()
+        );
+        assert!(
+            elapsed < std::time::Duration::from_secs(3),
+            "jobs should overlap, took {:?}",
+            elapsed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_results_preserve_input_order_regardless_of_completion_order() {
+        // "A" sleeps longer than "B", so it finishes second; the report
+        // must still list results in input order ([A, B]), not completion
+        // order ([B, A]).
+        let jobs = vec![
+            CommandJob {
+                command: "sleep 1".to_string(),
+                ..sleep_job("A")
+            },
+            CommandJob {
+                command: "true".to_string(),
+                ..sleep_job("B")
+            },
+        ];
+
+        let report = run_command_jobs(jobs, 2).await;
+        let names: Vec<&str> = report.results.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["A", "B"]);
+    }
+
+    #[tokio::test]
+    async fn test_max_parallel_bounds_concurrency() {
+        // Bounded to 1-way concurrency, two 1s sleeps must run back-to-back.
+        let jobs = vec![sleep_job("A"), sleep_job("B")];
+
+        let start = std::time::Instant::now();
+        let report = run_command_jobs(jobs, 1).await;
+        let elapsed = start.elapsed();
+
+        assert!(report.results.iter().all(|(_, r)| r.is_ok()));
+        assert!(
+            elapsed >= std::time::Duration::from_secs(2),
+            "max_parallel=1 should serialize the two jobs, took {:?}",
+            elapsed
+        );
+    }
+}
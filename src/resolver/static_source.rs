@@ -1,13 +1,17 @@
 use anyhow::{bail, Result};
+use std::collections::HashMap;
 
 use crate::template;
 
-/// Resolve a static variable value for the given environment.
+/// Resolve a static variable value for the given environment. `resolved_vars`
+/// supplies any other variables' already-resolved values, for `{var:name}`
+/// cross-references (see [`crate::schema::dependency`]).
 pub fn resolve_static(
     var_name: &str,
     values: &std::collections::BTreeMap<String, String>,
     env_name: &str,
     env_config: &std::collections::BTreeMap<String, String>,
+    resolved_vars: &HashMap<String, String>,
 ) -> Result<String> {
     let raw_value = match values.get(env_name) {
         Some(v) => v,
@@ -19,7 +23,8 @@ pub fn resolve_static(
     };
 
     // Expand any template placeholders in the static value
-    let ctx = template::build_context(env_name, env_config, var_name);
+    let mut ctx = template::build_context(env_name, env_config, var_name);
+    template::apply_resolved_variables(&mut ctx, resolved_vars);
     template::expand_template(raw_value, &ctx)
 }
 
@@ -34,7 +39,8 @@ mod tests {
         values.insert("local".to_string(), "hello".to_string());
         let env_config = BTreeMap::new();
 
-        let result = resolve_static("MY_VAR", &values, "local", &env_config).unwrap();
+        let result =
+            resolve_static("MY_VAR", &values, "local", &env_config, &HashMap::new()).unwrap();
         assert_eq!(result, "hello");
     }
 
@@ -45,7 +51,8 @@ mod tests {
         let mut env_config = BTreeMap::new();
         env_config.insert("project".to_string(), "myapp".to_string());
 
-        let result = resolve_static("DB_NAME", &values, "local", &env_config).unwrap();
+        let result =
+            resolve_static("DB_NAME", &values, "local", &env_config, &HashMap::new()).unwrap();
         assert_eq!(result, "myapp-db");
     }
 
@@ -53,7 +60,21 @@ mod tests {
     fn test_resolve_static_missing_env() {
         let values = BTreeMap::new();
         let env_config = BTreeMap::new();
-        let result = resolve_static("MY_VAR", &values, "production", &env_config);
+        let result =
+            resolve_static("MY_VAR", &values, "production", &env_config, &HashMap::new());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_resolve_static_with_variable_reference() {
+        let mut values = BTreeMap::new();
+        values.insert("local".to_string(), "{var:BASE}-suffix".to_string());
+        let env_config = BTreeMap::new();
+        let mut resolved_vars = HashMap::new();
+        resolved_vars.insert("BASE".to_string(), "root".to_string());
+
+        let result =
+            resolve_static("DERIVED", &values, "local", &env_config, &resolved_vars).unwrap();
+        assert_eq!(result, "root-suffix");
+    }
 }
@@ -0,0 +1,353 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::parser::parse_schema_file;
+use super::types::{Metadata, Schema, Variable};
+
+/// Loads the schema at `path`, recursively resolving and deep-merging any
+/// `extends` parents before returning. Parent paths are resolved relative to
+/// the file that declares them. The returned `Schema` never has `extends`
+/// set, since by the time it's returned the chain has already been merged
+/// away.
+pub fn load_composed_schema_file(path: &Path) -> Result<Schema> {
+    let mut chain = Vec::new();
+    load_composed(path, &mut chain)
+}
+
+fn load_composed(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Schema> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read schema file: {}", path.display()))?;
+
+    if chain.contains(&canonical) {
+        let cycle = chain
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect::<Vec<String>>()
+            .join(" -> ");
+        bail!("Circular `extends` chain: {}", cycle);
+    }
+
+    let schema = parse_schema_file(path)?;
+
+    let Some(parent_paths) = schema.extends.clone() else {
+        return Ok(schema);
+    };
+    if parent_paths.is_empty() {
+        return Ok(strip_extends(schema));
+    }
+
+    chain.push(canonical);
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut merged: Option<Schema> = None;
+    for relative in &parent_paths {
+        let parent_path = base_dir.join(relative);
+        let parent_schema = load_composed(&parent_path, chain)?;
+        merged = Some(match merged {
+            Some(acc) => merge_schema(acc, parent_schema),
+            None => parent_schema,
+        });
+    }
+
+    chain.pop();
+
+    let base = merged.expect("parent_paths is non-empty, so merged is always populated");
+    Ok(strip_extends(merge_schema(base, schema)))
+}
+
+fn strip_extends(mut schema: Schema) -> Schema {
+    schema.extends = None;
+    schema
+}
+
+/// Returns the canonical path of `path` together with every file in its
+/// `extends` chain, so a caller that needs to know everything which can
+/// affect a composed schema (see [`crate::commands::watch::run_watch`]) isn't
+/// limited to just the root file. Silently stops descending into a parent
+/// it's already visited rather than erroring on a cycle: schema loading
+/// already rejects cycles, and this is only ever used to pick watch targets.
+pub fn schema_chain_paths(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut seen = Vec::new();
+    let mut out = Vec::new();
+    collect_chain_paths(path, &mut seen, &mut out)?;
+    Ok(out)
+}
+
+fn collect_chain_paths(path: &Path, seen: &mut Vec<PathBuf>, out: &mut Vec<PathBuf>) -> Result<()> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read schema file: {}", path.display()))?;
+
+    if seen.contains(&canonical) {
+        return Ok(());
+    }
+    seen.push(canonical.clone());
+    out.push(canonical);
+
+    let schema = parse_schema_file(path)?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    if let Some(parent_paths) = &schema.extends {
+        for relative in parent_paths {
+            collect_chain_paths(&base_dir.join(relative), seen, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Deep-merges `child` over `base`: `environments`, `sources`, and
+/// `variables` are merged key-by-key with `child` overriding `base`, and
+/// `metadata.destination` merges the same way. Within a `Variable` present
+/// in both, fields the child actually sets (a `Some`, or the field has no
+/// default to fall back to) replace the base's; unset `Option` fields are
+/// inherited from the base.
+fn merge_schema(base: Schema, child: Schema) -> Schema {
+    let mut destination = base.metadata.destination;
+    destination.extend(child.metadata.destination);
+
+    let mut environments = base.environments;
+    for (name, child_env) in child.environments {
+        environments
+            .entry(name)
+            .and_modify(|base_env| {
+                base_env.extends = child_env.extends.clone().or_else(|| base_env.extends.clone());
+                base_env.config.extend(child_env.config.clone());
+            })
+            .or_insert(child_env);
+    }
+
+    let mut sources = base.sources;
+    sources.extend(child.sources);
+
+    let mut variables = base.variables;
+    for (name, child_var) in child.variables {
+        variables
+            .entry(name)
+            .and_modify(|base_var| *base_var = merge_variable(base_var, &child_var))
+            .or_insert(child_var);
+    }
+
+    Schema {
+        schema_version: child.schema_version,
+        metadata: Metadata {
+            description: child.metadata.description,
+            destination,
+            command_allowlist: if child.metadata.command_allowlist.is_empty() {
+                base.metadata.command_allowlist
+            } else {
+                child.metadata.command_allowlist
+            },
+        },
+        environments,
+        sources,
+        variables,
+        extends: None,
+    }
+}
+
+/// Merges a child variable definition over its base counterpart. `required`
+/// fields like `description` always take the child's value, since a fully
+/// parsed `Variable` can't distinguish "the child explicitly repeated this"
+/// from "the child left it at its default" for non-`Option` fields.
+/// `Option` fields fall back to the base's value when the child leaves them
+/// unset.
+fn merge_variable(base: &Variable, child: &Variable) -> Variable {
+    Variable {
+        description: child.description.clone(),
+        sensitive: child.sensitive,
+        source: child.source.clone().or_else(|| base.source.clone()),
+        source_key: child.source_key.clone().or_else(|| base.source_key.clone()),
+        source_instructions: child
+            .source_instructions
+            .clone()
+            .or_else(|| base.source_instructions.clone()),
+        environments: child
+            .environments
+            .clone()
+            .or_else(|| base.environments.clone()),
+        choices: child.choices.clone().or_else(|| base.choices.clone()),
+        pattern: child.pattern.clone().or_else(|| base.pattern.clone()),
+        default: child.default.clone().or_else(|| base.default.clone()),
+        values: child.values.clone().or_else(|| base.values.clone()),
+        resolvers: child.resolvers.clone().or_else(|| base.resolvers.clone()),
+        required: child.required,
+        constraints: child
+            .constraints
+            .clone()
+            .or_else(|| base.constraints.clone()),
+        notes: child.notes.clone().or_else(|| base.notes.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_schema(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_child_inherits_and_overrides_base() {
+        let dir = tempfile::tempdir().unwrap();
+
+        write_schema(
+            dir.path(),
+            "base.yaml",
+            r#"
+schema_version: "2"
+metadata:
+  description: "Base"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "base-project"
+sources: {}
+variables:
+  SHARED:
+    description: "Shared var from base"
+    source: static
+    values:
+      local: "base-value"
+  BASE_ONLY:
+    description: "Only in base"
+    source: static
+    required: false
+    values:
+      local: "base-only-value"
+"#,
+        );
+
+        let child_path = write_schema(
+            dir.path(),
+            "child.yaml",
+            r#"
+schema_version: "2"
+metadata:
+  description: "Child"
+  destination:
+    staging: ".env.staging"
+extends: ["base.yaml"]
+environments:
+  staging:
+    project: "child-project"
+sources: {}
+variables:
+  SHARED:
+    description: "Shared var from base"
+    source: static
+    values:
+      local: "child-value"
+"#,
+        );
+
+        let schema = load_composed_schema_file(&child_path).unwrap();
+
+        assert!(schema.extends.is_none());
+        assert_eq!(schema.metadata.destination.get("local").unwrap(), ".env");
+        assert_eq!(
+            schema.metadata.destination.get("staging").unwrap(),
+            ".env.staging"
+        );
+        assert!(schema.environments.contains_key("local"));
+        assert!(schema.environments.contains_key("staging"));
+        assert_eq!(
+            schema.variables["SHARED"].values.as_ref().unwrap()["local"],
+            "child-value"
+        );
+        assert!(schema.variables.contains_key("BASE_ONLY"));
+    }
+
+    #[test]
+    fn test_cycle_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let a_path = write_schema(
+            dir.path(),
+            "a.yaml",
+            r#"
+schema_version: "2"
+metadata:
+  description: "A"
+  destination:
+    local: ".env"
+extends: ["b.yaml"]
+environments:
+  local:
+    project: "a"
+sources: {}
+variables: {}
+"#,
+        );
+
+        write_schema(
+            dir.path(),
+            "b.yaml",
+            r#"
+schema_version: "2"
+metadata:
+  description: "B"
+  destination:
+    local: ".env"
+extends: ["a.yaml"]
+environments:
+  local:
+    project: "b"
+sources: {}
+variables: {}
+"#,
+        );
+
+        let err = load_composed_schema_file(&a_path).unwrap_err();
+        assert!(err.to_string().contains("Circular `extends` chain"));
+    }
+
+    #[test]
+    fn test_schema_chain_paths_includes_extends_parents() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let base_path = write_schema(
+            dir.path(),
+            "base.yaml",
+            r#"
+schema_version: "2"
+metadata:
+  description: "Base"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "base-project"
+sources: {}
+variables: {}
+"#,
+        );
+
+        let child_path = write_schema(
+            dir.path(),
+            "child.yaml",
+            r#"
+schema_version: "2"
+metadata:
+  description: "Child"
+  destination:
+    local: ".env"
+extends: ["base.yaml"]
+environments:
+  local:
+    project: "child-project"
+sources: {}
+variables: {}
+"#,
+        );
+
+        let paths = schema_chain_paths(&child_path).unwrap();
+        assert_eq!(paths, vec![child_path.canonicalize().unwrap(), base_path.canonicalize().unwrap()]);
+    }
+}
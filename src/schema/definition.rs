@@ -0,0 +1,544 @@
+use serde_json::{json, Map, Value};
+
+/// Typed builders for Draft 2020-12 JSON Schema documents.
+///
+/// The embedded schema (`super::json_schema()`) is generated from
+/// [`build_schema`] instead of being hand-maintained as a JSON file. The
+/// shape described here mirrors the shape [`crate::schema::types`]
+/// deserializes into; it is written alongside those types, not derived from
+/// them.
+
+#[derive(Debug, Clone, Default)]
+pub struct ObjectSchema {
+    description: Option<String>,
+    properties: Vec<(String, Value)>,
+    required: Vec<String>,
+    pattern_properties: Vec<(String, Value)>,
+    additional_properties: Option<Box<Value>>,
+}
+
+impl ObjectSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn property(mut self, name: impl Into<String>, schema: impl Into<Value>) -> Self {
+        self.properties.push((name.into(), schema.into()));
+        self
+    }
+
+    pub fn required(mut self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.required = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn pattern_property(mut self, pattern: impl Into<String>, schema: impl Into<Value>) -> Self {
+        self.pattern_properties.push((pattern.into(), schema.into()));
+        self
+    }
+
+    pub fn additional_properties(mut self, schema: impl Into<Value>) -> Self {
+        self.additional_properties = Some(Box::new(schema.into()));
+        self
+    }
+}
+
+impl From<ObjectSchema> for Value {
+    fn from(schema: ObjectSchema) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), json!("object"));
+        if let Some(description) = schema.description {
+            obj.insert("description".to_string(), json!(description));
+        }
+        if !schema.properties.is_empty() {
+            let mut properties = Map::new();
+            for (name, value) in schema.properties {
+                properties.insert(name, value);
+            }
+            obj.insert("properties".to_string(), Value::Object(properties));
+        }
+        if !schema.required.is_empty() {
+            obj.insert("required".to_string(), json!(schema.required));
+        }
+        if !schema.pattern_properties.is_empty() {
+            let mut pattern_properties = Map::new();
+            for (pattern, value) in schema.pattern_properties {
+                pattern_properties.insert(pattern, value);
+            }
+            obj.insert("patternProperties".to_string(), Value::Object(pattern_properties));
+        }
+        if let Some(additional) = schema.additional_properties {
+            obj.insert("additionalProperties".to_string(), *additional);
+        }
+        Value::Object(obj)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StringSchema {
+    description: Option<String>,
+    min_length: Option<u64>,
+    max_length: Option<u64>,
+    pattern: Option<String>,
+    format: Option<String>,
+    enum_values: Vec<String>,
+}
+
+impl StringSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn min_length(mut self, min_length: u64) -> Self {
+        self.min_length = Some(min_length);
+        self
+    }
+
+    pub fn max_length(mut self, max_length: u64) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    pub fn format(mut self, format: impl Into<String>) -> Self {
+        self.format = Some(format.into());
+        self
+    }
+
+    pub fn enum_values(mut self, values: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.enum_values = values.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl From<StringSchema> for Value {
+    fn from(schema: StringSchema) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), json!("string"));
+        if let Some(description) = schema.description {
+            obj.insert("description".to_string(), json!(description));
+        }
+        if let Some(min_length) = schema.min_length {
+            obj.insert("minLength".to_string(), json!(min_length));
+        }
+        if let Some(max_length) = schema.max_length {
+            obj.insert("maxLength".to_string(), json!(max_length));
+        }
+        if let Some(pattern) = schema.pattern {
+            obj.insert("pattern".to_string(), json!(pattern));
+        }
+        if let Some(format) = schema.format {
+            obj.insert("format".to_string(), json!(format));
+        }
+        if !schema.enum_values.is_empty() {
+            obj.insert("enum".to_string(), json!(schema.enum_values));
+        }
+        Value::Object(obj)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IntegerSchema {
+    description: Option<String>,
+    minimum: Option<i64>,
+    maximum: Option<i64>,
+    enum_values: Vec<i64>,
+}
+
+impl IntegerSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn minimum(mut self, minimum: i64) -> Self {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    pub fn maximum(mut self, maximum: i64) -> Self {
+        self.maximum = Some(maximum);
+        self
+    }
+
+    pub fn enum_values(mut self, values: impl IntoIterator<Item = i64>) -> Self {
+        self.enum_values = values.into_iter().collect();
+        self
+    }
+}
+
+impl From<IntegerSchema> for Value {
+    fn from(schema: IntegerSchema) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), json!("integer"));
+        if let Some(description) = schema.description {
+            obj.insert("description".to_string(), json!(description));
+        }
+        if let Some(minimum) = schema.minimum {
+            obj.insert("minimum".to_string(), json!(minimum));
+        }
+        if let Some(maximum) = schema.maximum {
+            obj.insert("maximum".to_string(), json!(maximum));
+        }
+        if !schema.enum_values.is_empty() {
+            obj.insert("enum".to_string(), json!(schema.enum_values));
+        }
+        Value::Object(obj)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ArraySchema {
+    description: Option<String>,
+    items: Option<Box<Value>>,
+    min_items: Option<u64>,
+    max_items: Option<u64>,
+}
+
+impl ArraySchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn items(mut self, schema: impl Into<Value>) -> Self {
+        self.items = Some(Box::new(schema.into()));
+        self
+    }
+
+    pub fn min_items(mut self, min_items: u64) -> Self {
+        self.min_items = Some(min_items);
+        self
+    }
+
+    pub fn max_items(mut self, max_items: u64) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+}
+
+impl From<ArraySchema> for Value {
+    fn from(schema: ArraySchema) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), json!("array"));
+        if let Some(description) = schema.description {
+            obj.insert("description".to_string(), json!(description));
+        }
+        if let Some(items) = schema.items {
+            obj.insert("items".to_string(), *items);
+        }
+        if let Some(min_items) = schema.min_items {
+            obj.insert("minItems".to_string(), json!(min_items));
+        }
+        if let Some(max_items) = schema.max_items {
+            obj.insert("maxItems".to_string(), json!(max_items));
+        }
+        Value::Object(obj)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NumberSchema {
+    description: Option<String>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+}
+
+impl NumberSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn minimum(mut self, minimum: f64) -> Self {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    pub fn maximum(mut self, maximum: f64) -> Self {
+        self.maximum = Some(maximum);
+        self
+    }
+}
+
+impl From<NumberSchema> for Value {
+    fn from(schema: NumberSchema) -> Value {
+        let mut obj = Map::new();
+        obj.insert("type".to_string(), json!("number"));
+        if let Some(description) = schema.description {
+            obj.insert("description".to_string(), json!(description));
+        }
+        if let Some(minimum) = schema.minimum {
+            obj.insert("minimum".to_string(), json!(minimum));
+        }
+        if let Some(maximum) = schema.maximum {
+            obj.insert("maximum".to_string(), json!(maximum));
+        }
+        Value::Object(obj)
+    }
+}
+
+fn boolean_schema(description: &str) -> Value {
+    json!({ "type": "boolean", "description": description })
+}
+
+/// Schema for a `sources.*` entry (mirrors [`crate::schema::types::Source`]).
+fn source_schema() -> Value {
+    ObjectSchema::new()
+        .description("A named command that resolves one or more variables for an environment")
+        .required(["command"])
+        .property(
+            "command",
+            StringSchema::new().description("Shell command template to run to resolve this source"),
+        )
+        .property(
+            "stdin_template",
+            StringSchema::new().description(
+                "Template piped to the command's stdin instead of substituted into the command line, so secrets it contains never appear in the command's argv",
+            ),
+        )
+        .property("label", StringSchema::new().description("Human-readable label shown to users"))
+        .property("url", StringSchema::new().description("Reference URL for where this value comes from"))
+        .property("description", StringSchema::new().description("Longer explanation of this source"))
+        .additional_properties(false)
+        .into()
+}
+
+/// Schema for a `variables.*.resolvers[]` entry (mirrors
+/// [`crate::schema::types::VariableResolver`]).
+fn variable_resolver_schema() -> Value {
+    ObjectSchema::new()
+        .description("Per-environment resolver binding for a schema v2 variable")
+        .required(["environments", "source"])
+        .property(
+            "environments",
+            ArraySchema::new()
+                .description("Environment names this resolver applies to")
+                .items(StringSchema::new()),
+        )
+        .property("source", StringSchema::new().description("Key into `sources`, or `static` / `manual` / `env`"))
+        .property("label", StringSchema::new())
+        .property("url", StringSchema::new())
+        .property("description", StringSchema::new())
+        .property("source_key", StringSchema::new().description("Key to use in source command templates"))
+        .property(
+            "values",
+            ObjectSchema::new()
+                .description("Inline values per environment (required when source = \"static\")")
+                .additional_properties(StringSchema::new()),
+        )
+        .property(
+            "choices",
+            ArraySchema::new()
+                .description("Manual source only: offered as a selection menu instead of free text")
+                .items(StringSchema::new()),
+        )
+        .property(
+            "pattern",
+            StringSchema::new()
+                .description("Manual source only: regex the entered value must match"),
+        )
+        .property(
+            "default",
+            StringSchema::new()
+                .description("Manual source only: pre-filled/accept-on-enter answer"),
+        )
+        .property(
+            "when",
+            StringSchema::new().description(
+                "Gates this resolver on the environment's config: only applies when this expression evaluates to true",
+            ),
+        )
+        .additional_properties(false)
+        .into()
+}
+
+/// Schema for a `variables.*.constraints` entry (mirrors
+/// [`crate::schema::types::Constraints`]).
+fn constraints_schema() -> Value {
+    ObjectSchema::new()
+        .description("Validation rules enforced on this variable's resolved value")
+        .property(
+            "pattern",
+            StringSchema::new().description("Regex the resolved value must match"),
+        )
+        .property(
+            "enum",
+            ArraySchema::new()
+                .description("Allowed literal values")
+                .items(StringSchema::new()),
+        )
+        .property(
+            "min_length",
+            IntegerSchema::new().description("Minimum length of the resolved value"),
+        )
+        .property(
+            "max_length",
+            IntegerSchema::new().description("Maximum length of the resolved value"),
+        )
+        .property(
+            "minimum",
+            NumberSchema::new()
+                .description("Minimum numeric value, for resolved values that parse as numbers"),
+        )
+        .property(
+            "maximum",
+            NumberSchema::new()
+                .description("Maximum numeric value, for resolved values that parse as numbers"),
+        )
+        .additional_properties(false)
+        .into()
+}
+
+/// Schema for a `variables.*` entry (mirrors [`crate::schema::types::Variable`]).
+fn variable_schema() -> Value {
+    ObjectSchema::new()
+        .description("A single environment variable and how to resolve its value")
+        .required(["description"])
+        .property(
+            "description",
+            StringSchema::new().description("Human-readable explanation of what this variable is for"),
+        )
+        .property("sensitive", boolean_schema("Whether this variable's value should be masked when printed"))
+        .property(
+            "source",
+            StringSchema::new().description("Key into `sources`, or `static` / `manual` / `env`. Omit when using `resolvers`"),
+        )
+        .property("source_key", StringSchema::new().description("Key to use in source command templates"))
+        .property("source_instructions", StringSchema::new())
+        .property(
+            "environments",
+            ArraySchema::new()
+                .description("Which environments this variable applies to; applies to all if omitted")
+                .items(StringSchema::new()),
+        )
+        .property(
+            "values",
+            ObjectSchema::new()
+                .description("Inline values per environment (required when source = \"static\")")
+                .additional_properties(StringSchema::new()),
+        )
+        .property(
+            "choices",
+            ArraySchema::new()
+                .description("Manual source only: offered as a selection menu instead of free text")
+                .items(StringSchema::new()),
+        )
+        .property(
+            "pattern",
+            StringSchema::new()
+                .description("Manual source only: regex the entered value must match"),
+        )
+        .property(
+            "default",
+            StringSchema::new()
+                .description("Manual source only: pre-filled/accept-on-enter answer"),
+        )
+        .property(
+            "resolvers",
+            ArraySchema::new()
+                .description("Schema v2: per-environment resolver bindings for this variable")
+                .items(variable_resolver_schema()),
+        )
+        .property("required", boolean_schema("Whether pull should fail if this variable cannot be resolved"))
+        .property("constraints", constraints_schema())
+        .property("notes", StringSchema::new())
+        .additional_properties(false)
+        .into()
+}
+
+/// Builds the full envgen schema document (mirrors [`crate::schema::types::Schema`]).
+pub fn build_schema() -> Value {
+    let metadata = ObjectSchema::new()
+        .description("Descriptive and destination information for the environments this schema defines")
+        .required(["description", "destination"])
+        .property("description", StringSchema::new())
+        .property(
+            "destination",
+            ObjectSchema::new()
+                .description("Destination file path per environment")
+                .additional_properties(StringSchema::new()),
+        )
+        .property(
+            "command_allowlist",
+            ArraySchema::new()
+                .description(
+                    "Regex patterns matched against a command source's fully expanded \
+                     command string. A command matching any pattern here may run \
+                     unattended; any other command source requires interactive \
+                     confirmation (or --yes / is refused outright by --deny-commands).",
+                )
+                .items(StringSchema::new()),
+        )
+        .additional_properties(false);
+
+    let environments = ObjectSchema::new()
+        .description(
+            "Environment name to arbitrary metadata map. An environment may set `extends` \
+             to another environment name to inherit its config, with its own keys overriding \
+             the inherited ones.",
+        )
+        .additional_properties(ObjectSchema::new().additional_properties(StringSchema::new()));
+
+    let sources = ObjectSchema::new()
+        .description("Named commands usable as a variable's `source`")
+        .additional_properties(source_schema());
+
+    let variables = ObjectSchema::new()
+        .description("Environment variables this schema resolves")
+        .additional_properties(variable_schema());
+
+    let extends = ArraySchema::new()
+        .description(
+            "Other schema files, relative to this one, to inherit `environments`, `sources`, \
+             and `variables` from. Later entries override earlier ones; this file's own \
+             entries override all of them.",
+        )
+        .items(StringSchema::new());
+
+    let root: Value = ObjectSchema::new()
+        .required(["schema_version", "metadata", "environments", "variables"])
+        .property("schema_version", StringSchema::new().description("envgen schema format version"))
+        .property("metadata", metadata)
+        .property("environments", environments)
+        .property("sources", sources)
+        .property("variables", variables)
+        .property("extends", extends)
+        .additional_properties(false)
+        .into();
+
+    let mut root = root;
+    root.as_object_mut()
+        .expect("root schema is always an object")
+        .insert(
+            "$schema".to_string(),
+            json!("https://json-schema.org/draft/2020-12/schema"),
+        );
+    root
+}
@@ -0,0 +1,366 @@
+//! Cross-variable `{var:X}` reference analysis: builds a per-environment
+//! dependency graph between variables, detects undefined/inapplicable
+//! references and reference cycles, and computes a safe resolution order
+//! (each variable's dependencies resolved before it) via Tarjan's
+//! strongly-connected-components algorithm.
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::types::Schema;
+use crate::template::{self, PlaceholderModifier};
+
+/// The template text used to resolve `var_name` in `env`, if any: the
+/// static value string for a static source, or the source command for a
+/// command source. Manual and env sources have no template to scan for
+/// `{var:X}` references.
+fn template_for_env<'a>(
+    schema: &'a Schema,
+    var_name: &str,
+    env: &str,
+    env_config: &BTreeMap<String, String>,
+) -> Option<&'a str> {
+    let var = schema.variables.get(var_name)?;
+    match var.effective_source_for_env(env, env_config)? {
+        "static" => var
+            .values_for_env(env, env_config)
+            .and_then(|values| values.get(env))
+            .map(|s| s.as_str()),
+        "manual" | "env" => None,
+        source_name => schema.sources.get(source_name).map(|s| s.command.as_str()),
+    }
+}
+
+/// Whether `var_name`'s effective source in `env` is a command source
+/// (i.e. not `static`/`manual`/`env`).
+fn is_command_sourced(
+    schema: &Schema,
+    var_name: &str,
+    env: &str,
+    env_config: &BTreeMap<String, String>,
+) -> bool {
+    match schema.variables.get(var_name).and_then(|var| var.effective_source_for_env(env, env_config)) {
+        Some(source) => !matches!(source, "static" | "manual" | "env"),
+        None => false,
+    }
+}
+
+/// The variable names that `var_name`'s template in `env` references via
+/// `{var:X}`.
+fn variable_refs(
+    schema: &Schema,
+    var_name: &str,
+    env: &str,
+    env_config: &BTreeMap<String, String>,
+) -> Vec<String> {
+    match template_for_env(schema, var_name, env, env_config) {
+        Some(template_str) => template::extract_placeholders(template_str)
+            .into_iter()
+            .filter(|ph| ph.modifier == PlaceholderModifier::VariableRef)
+            .map(|ph| ph.name)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Result of analyzing one environment's `{var:X}` reference graph.
+pub struct VariableDependencyAnalysis {
+    /// Applicable variable names in a safe resolution order: a variable's
+    /// `{var:X}` dependencies always appear before it. Only meaningful when
+    /// `errors` is empty.
+    pub order: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+/// Analyzes `env`'s `{var:X}` reference graph across all variables
+/// applicable to it, reporting undefined/inapplicable references and
+/// reference cycles, and computing a safe resolution order.
+pub fn analyze(schema: &Schema, env: &str) -> VariableDependencyAnalysis {
+    let applicable: Vec<String> = schema
+        .variables
+        .iter()
+        .filter(|(_, var)| var.applies_to(env))
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    let env_config = schema.resolved_env_config(env).unwrap_or_default();
+
+    let mut errors = Vec::new();
+    let mut edges: HashMap<String, Vec<String>> = HashMap::new();
+
+    for var_name in &applicable {
+        let own_is_command = is_command_sourced(schema, var_name, env, &env_config);
+        let mut deps = Vec::new();
+        for referenced in variable_refs(schema, var_name, env, &env_config) {
+            match schema.variables.get(&referenced) {
+                None => errors.push(format!(
+                    "{}: references undefined variable \"{{var:{}}}\".",
+                    var_name, referenced
+                )),
+                Some(ref_var) if !ref_var.applies_to(env) => errors.push(format!(
+                    "{}: references variable \"{{var:{}}}\" which does not apply to environment \"{}\".",
+                    var_name, referenced, env
+                )),
+                Some(_) if own_is_command && is_command_sourced(schema, &referenced, env, &env_config) => {
+                    errors.push(format!(
+                        "{}: command source references \"{{var:{}}}\", which is also command-sourced; command sources run concurrently and can't see each other's resolved values. Reference a static/env/manual variable instead.",
+                        var_name, referenced
+                    ));
+                }
+                Some(_) => deps.push(referenced),
+            }
+        }
+        edges.insert(var_name.clone(), deps);
+    }
+
+    if !errors.is_empty() {
+        return VariableDependencyAnalysis {
+            order: Vec::new(),
+            errors,
+        };
+    }
+
+    let (order, cycles) = strongly_connected_components(&applicable, &edges);
+    for cycle in cycles {
+        errors.push(format!(
+            "variable reference cycle: {}.",
+            describe_cycle(&cycle)
+        ));
+    }
+
+    VariableDependencyAnalysis { order, errors }
+}
+
+/// Renders a cyclic component as `A -> B -> A`, closing the loop back to
+/// its first member.
+fn describe_cycle(component: &[String]) -> String {
+    let mut path: Vec<String> = component.iter().rev().cloned().collect();
+    if let Some(first) = path.first().cloned() {
+        path.push(first);
+    }
+    path.join(" -> ")
+}
+
+/// Tarjan's algorithm. Emits each strongly connected component as soon as
+/// it is fully discovered, which happens in reverse topological order of
+/// the condensation: if `A` depends on `B`, `B`'s component is emitted
+/// before `A`'s. Components of size 1 with no self-loop are appended
+/// directly to `order`; everything else (a genuine cycle) is returned
+/// separately instead, since no resolution order satisfies it.
+fn strongly_connected_components(
+    nodes: &[String],
+    edges: &HashMap<String, Vec<String>>,
+) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut state = TarjanState::default();
+    for node in nodes {
+        if !state.index.contains_key(node) {
+            strong_connect(node, edges, &mut state);
+        }
+    }
+    (state.order, state.cycles)
+}
+
+#[derive(Default)]
+struct TarjanState {
+    index: HashMap<String, usize>,
+    lowlink: HashMap<String, usize>,
+    on_stack: HashMap<String, bool>,
+    stack: Vec<String>,
+    next_index: usize,
+    order: Vec<String>,
+    cycles: Vec<Vec<String>>,
+}
+
+fn strong_connect(node: &str, edges: &HashMap<String, Vec<String>>, state: &mut TarjanState) {
+    state.index.insert(node.to_string(), state.next_index);
+    state.lowlink.insert(node.to_string(), state.next_index);
+    state.next_index += 1;
+    state.stack.push(node.to_string());
+    state.on_stack.insert(node.to_string(), true);
+
+    if let Some(deps) = edges.get(node) {
+        for dep in deps {
+            if !state.index.contains_key(dep) {
+                strong_connect(dep, edges, state);
+                let merged = state.lowlink[node].min(state.lowlink[dep]);
+                state.lowlink.insert(node.to_string(), merged);
+            } else if *state.on_stack.get(dep).unwrap_or(&false) {
+                let merged = state.lowlink[node].min(state.index[dep]);
+                state.lowlink.insert(node.to_string(), merged);
+            }
+        }
+    }
+
+    if state.lowlink[node] == state.index[node] {
+        let mut component = Vec::new();
+        loop {
+            let member = state.stack.pop().expect("node's own SCC is on the stack");
+            state.on_stack.insert(member.clone(), false);
+            let is_node = member == node;
+            component.push(member);
+            if is_node {
+                break;
+            }
+        }
+
+        let self_loop = edges.get(node).is_some_and(|deps| deps.iter().any(|d| d == node));
+        if component.len() > 1 || self_loop {
+            state.cycles.push(component);
+        } else {
+            state.order.extend(component);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::parser::parse_schema;
+
+    fn schema_with_values(values: &[(&str, &str)]) -> Schema {
+        let vars = values
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "  {}:\n    description: \"A variable\"\n    source: static\n    values:\n      local: \"{}\"\n",
+                    name, value
+                )
+            })
+            .collect::<String>();
+        let yaml = format!(
+            "schema_version: \"2\"\nmetadata:\n  description: \"Test\"\n  destination:\n    local: \".env\"\nenvironments:\n  local:\n    project: \"test\"\nsources: {{}}\nvariables:\n{}",
+            vars
+        );
+        parse_schema(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_no_references_resolves_in_any_order() {
+        let schema = schema_with_values(&[("A", "a"), ("B", "b")]);
+        let analysis = analyze(&schema, "local");
+        assert!(analysis.errors.is_empty());
+        assert_eq!(analysis.order.len(), 2);
+    }
+
+    #[test]
+    fn test_dependency_resolves_before_dependent() {
+        let schema = schema_with_values(&[("A", "{var:B}-suffix"), ("B", "base")]);
+        let analysis = analyze(&schema, "local");
+        assert!(analysis.errors.is_empty(), "errors: {:?}", analysis.errors);
+        let pos_a = analysis.order.iter().position(|n| n == "A").unwrap();
+        let pos_b = analysis.order.iter().position(|n| n == "B").unwrap();
+        assert!(pos_b < pos_a, "B must resolve before A, got {:?}", analysis.order);
+    }
+
+    #[test]
+    fn test_undefined_variable_reference_is_an_error() {
+        let schema = schema_with_values(&[("A", "{var:MISSING}")]);
+        let analysis = analyze(&schema, "local");
+        assert!(
+            analysis
+                .errors
+                .iter()
+                .any(|e| e.contains("A") && e.contains("undefined variable") && e.contains("MISSING")),
+            "errors: {:?}",
+            analysis.errors
+        );
+    }
+
+    #[test]
+    fn test_reference_cycle_is_reported() {
+        let schema = schema_with_values(&[("A", "{var:B}"), ("B", "{var:A}")]);
+        let analysis = analyze(&schema, "local");
+        assert!(
+            analysis.errors.iter().any(|e| e.contains("reference cycle")),
+            "errors: {:?}",
+            analysis.errors
+        );
+    }
+
+    #[test]
+    fn test_self_reference_is_a_cycle() {
+        let schema = schema_with_values(&[("A", "{var:A}")]);
+        let analysis = analyze(&schema, "local");
+        assert!(
+            analysis.errors.iter().any(|e| e.contains("reference cycle")),
+            "errors: {:?}",
+            analysis.errors
+        );
+    }
+
+    #[test]
+    fn test_command_source_referencing_command_source_is_an_error() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources:
+  cmd_a:
+    command: "echo {var:B}"
+  cmd_b:
+    command: "echo {key}"
+variables:
+  A:
+    description: "A variable"
+    source: cmd_a
+  B:
+    description: "A variable"
+    source: cmd_b
+"#;
+        let schema = parse_schema(yaml).unwrap();
+        let analysis = analyze(&schema, "local");
+        assert!(
+            analysis
+                .errors
+                .iter()
+                .any(|e| e.contains("A") && e.contains("command-sourced") && e.contains("B")),
+            "errors: {:?}",
+            analysis.errors
+        );
+    }
+
+    #[test]
+    fn test_reference_to_inapplicable_variable_is_an_error() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+    staging: ".env.staging"
+environments:
+  local:
+    project: "test"
+  staging:
+    project: "test"
+sources: {}
+variables:
+  A:
+    description: "A variable"
+    source: static
+    values:
+      local: "{var:B}"
+      staging: "{var:B}"
+  B:
+    description: "A variable"
+    source: static
+    environments: [staging]
+    values:
+      staging: "b"
+"#;
+        let schema = parse_schema(yaml).unwrap();
+        let analysis = analyze(&schema, "local");
+        assert!(
+            analysis
+                .errors
+                .iter()
+                .any(|e| e.contains("does not apply to environment \"local\"")),
+            "errors: {:?}",
+            analysis.errors
+        );
+    }
+}
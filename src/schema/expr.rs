@@ -0,0 +1,328 @@
+//! A tiny self-contained expression evaluator for `when` conditions on
+//! resolvers (see [`super::types::VariableResolver::when`]). Expressions
+//! compare identifiers (resolved against an environment's config map, plus
+//! `key`/`environment`) and string/number literals with `==`, `!=`, `&&`,
+//! `||`, `!`, and parentheses. An unknown identifier evaluates to the empty
+//! string rather than erroring, so a `when` clause referencing a config key
+//! another environment doesn't define simply reads as `""`.
+
+use std::collections::HashMap;
+
+/// A parsed `when` expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Ident(String),
+    Literal(String),
+    Eq(Box<Expr>, Box<Expr>),
+    Ne(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Literal(String),
+    Eq,
+    Ne,
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Ne);
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Eq);
+                i += 2;
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '"' | '\'' => {
+                let quote = c;
+                i += 1;
+                let start = i;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(format!("unterminated string literal starting at {}", start));
+                }
+                tokens.push(Token::Literal(chars[start..i].iter().collect()));
+                i += 1;
+            }
+            _ if c.is_ascii_digit() => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                tokens.push(Token::Literal(chars[start..i].iter().collect()));
+            }
+            _ if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            other => return Err(format!("unexpected character '{}' at position {}", other, i)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    // Precedence, loosest to tightest: `||` < `&&` < comparison < unary `!` < primary.
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_comparison()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            let rhs = self.parse_comparison()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr, String> {
+        let lhs = self.parse_unary()?;
+        match self.peek() {
+            Some(Token::Eq) => {
+                self.pos += 1;
+                let rhs = self.parse_unary()?;
+                Ok(Expr::Eq(Box::new(lhs), Box::new(rhs)))
+            }
+            Some(Token::Ne) => {
+                self.pos += 1;
+                let rhs = self.parse_unary()?;
+                Ok(Expr::Ne(Box::new(lhs), Box::new(rhs)))
+            }
+            _ => Ok(lhs),
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            let operand = self.parse_unary()?;
+            return Ok(Expr::Not(Box::new(operand)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Ident(name)) => {
+                self.pos += 1;
+                Ok(Expr::Ident(name.clone()))
+            }
+            Some(Token::Literal(value)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(value.clone()))
+            }
+            Some(Token::LParen) => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                match self.tokens.get(self.pos) {
+                    Some(Token::RParen) => {
+                        self.pos += 1;
+                        Ok(inner)
+                    }
+                    _ => Err("expected closing parenthesis".to_string()),
+                }
+            }
+            Some(other) => Err(format!("unexpected token {:?}", other)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+/// Parses a `when` expression into an AST, or an error describing the parse
+/// failure.
+pub fn parse(input: &str) -> Result<Expr, String> {
+    let tokens = tokenize(input)?;
+    let mut parser = Parser { tokens: &tokens, pos: 0 };
+    let expr = parser.parse_or()?;
+    if parser.pos != tokens.len() {
+        return Err("unexpected trailing tokens after expression".to_string());
+    }
+    Ok(expr)
+}
+
+/// Evaluates `expr` against `ctx` (typically built via
+/// [`crate::template::build_context`]). An identifier missing from `ctx`
+/// evaluates as the empty string. `&&`/`||`/`!` coerce a bare identifier or
+/// literal operand to a boolean by non-emptiness.
+pub fn evaluate(expr: &Expr, ctx: &HashMap<String, String>) -> bool {
+    eval_value(expr, ctx).truthy()
+}
+
+enum Value {
+    Str(String),
+    Bool(bool),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Bool(b) => *b,
+            Value::Str(s) => !s.is_empty(),
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Value::Bool(b) if *b => "true",
+            Value::Bool(_) => "",
+            Value::Str(s) => s,
+        }
+    }
+}
+
+fn eval_value(expr: &Expr, ctx: &HashMap<String, String>) -> Value {
+    match expr {
+        Expr::Ident(name) => Value::Str(ctx.get(name).cloned().unwrap_or_default()),
+        Expr::Literal(value) => Value::Str(value.clone()),
+        Expr::Eq(lhs, rhs) => {
+            Value::Bool(eval_value(lhs, ctx).as_str() == eval_value(rhs, ctx).as_str())
+        }
+        Expr::Ne(lhs, rhs) => {
+            Value::Bool(eval_value(lhs, ctx).as_str() != eval_value(rhs, ctx).as_str())
+        }
+        Expr::And(lhs, rhs) => {
+            Value::Bool(eval_value(lhs, ctx).truthy() && eval_value(rhs, ctx).truthy())
+        }
+        Expr::Or(lhs, rhs) => {
+            Value::Bool(eval_value(lhs, ctx).truthy() || eval_value(rhs, ctx).truthy())
+        }
+        Expr::Not(operand) => Value::Bool(!eval_value(operand, ctx).truthy()),
+    }
+}
+
+/// Parses and evaluates `when` in one step, for callers that don't need to
+/// keep the AST around. Returns `false` on a parse error, since
+/// `validate_schema` is expected to have already rejected malformed `when`
+/// expressions before this is ever called at resolve time.
+pub fn eval_when(when: &str, ctx: &HashMap<String, String>) -> bool {
+    parse(when).map(|expr| evaluate(&expr, ctx)).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple_equality() {
+        let expr = parse("tier == \"paid\"").unwrap();
+        assert!(evaluate(&expr, &ctx(&[("tier", "paid")])));
+        assert!(!evaluate(&expr, &ctx(&[("tier", "free")])));
+    }
+
+    #[test]
+    fn test_inequality() {
+        let expr = parse("region != \"eu\"").unwrap();
+        assert!(evaluate(&expr, &ctx(&[("region", "us")])));
+        assert!(!evaluate(&expr, &ctx(&[("region", "eu")])));
+    }
+
+    #[test]
+    fn test_and_or_precedence() {
+        let expr = parse("tier == \"paid\" && region != \"eu\" || tier == \"trial\"").unwrap();
+        assert!(evaluate(&expr, &ctx(&[("tier", "paid"), ("region", "us")])));
+        assert!(!evaluate(&expr, &ctx(&[("tier", "paid"), ("region", "eu")])));
+        assert!(evaluate(&expr, &ctx(&[("tier", "trial"), ("region", "eu")])));
+    }
+
+    #[test]
+    fn test_negation_and_parentheses() {
+        let expr = parse("!(tier == \"free\")").unwrap();
+        assert!(evaluate(&expr, &ctx(&[("tier", "paid")])));
+        assert!(!evaluate(&expr, &ctx(&[("tier", "free")])));
+    }
+
+    #[test]
+    fn test_unknown_identifier_compares_as_empty_string() {
+        let expr = parse("missing == \"\"").unwrap();
+        assert!(evaluate(&expr, &ctx(&[])));
+    }
+
+    #[test]
+    fn test_built_in_environment_identifier() {
+        let expr = parse("environment == \"prod\"").unwrap();
+        assert!(evaluate(&expr, &ctx(&[("environment", "prod")])));
+    }
+
+    #[test]
+    fn test_malformed_expression_is_an_error() {
+        assert!(parse("tier ==").is_err());
+        assert!(parse("(tier == \"paid\"").is_err());
+        assert!(parse("tier @ \"paid\"").is_err());
+    }
+
+    #[test]
+    fn test_unterminated_string_literal_is_an_error() {
+        assert!(parse("tier == \"paid").is_err());
+    }
+}
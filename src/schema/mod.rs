@@ -1,22 +1,34 @@
+pub mod compose;
+pub mod definition;
+pub mod dependency;
+pub mod expr;
 pub mod parser;
 pub mod structural;
 pub mod types;
 pub mod validation;
 pub mod validator;
 
+use std::sync::OnceLock;
+
 pub const JSON_SCHEMA_FILENAME: &str =
     concat!("envgen.schema.v", env!("CARGO_PKG_VERSION"), ".json");
 
-pub const JSON_SCHEMA: &str = include_str!(concat!(
-    env!("CARGO_MANIFEST_DIR"),
-    "/schemas/envgen.schema.v",
-    env!("CARGO_PKG_VERSION"),
-    ".json"
-));
+static JSON_SCHEMA_CELL: OnceLock<String> = OnceLock::new();
+
+/// The Draft 2020-12 JSON Schema for envgen schema files, generated from the
+/// typed definitions in [`definition`] rather than hand-maintained as a JSON
+/// file. `run_schema_print`/`run_schema_export` and the structural validator
+/// all read through this accessor, so they can't drift apart.
+pub fn json_schema() -> &'static str {
+    JSON_SCHEMA_CELL.get_or_init(|| {
+        serde_json::to_string_pretty(&definition::build_schema())
+            .expect("generated schema must serialize to JSON")
+    })
+}
 
 #[cfg(test)]
 mod tests {
-    use super::JSON_SCHEMA;
+    use super::json_schema;
     use serde_json::Value;
     #[cfg(target_os = "linux")]
     use std::io::Write;
@@ -27,17 +39,17 @@ mod tests {
 
     #[test]
     fn embedded_schema_is_valid_json() {
-        let result: Result<Value, _> = serde_json::from_str(JSON_SCHEMA);
+        let result: Result<Value, _> = serde_json::from_str(json_schema());
         assert!(
             result.is_ok(),
-            "schema.json is not valid JSON: {}",
+            "generated schema is not valid JSON: {}",
             result.unwrap_err()
         );
     }
 
     #[test]
     fn embedded_schema_declares_draft_2020_12() {
-        let schema: Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+        let schema: Value = serde_json::from_str(json_schema()).unwrap();
         assert_eq!(
             schema.get("$schema").and_then(|v| v.as_str()),
             Some(EXPECTED_SCHEMA_DRAFT),
@@ -56,8 +68,8 @@ mod tests {
         }
 
         let mut tmp = tempfile::NamedTempFile::new().expect("create temp schema file");
-        tmp.write_all(JSON_SCHEMA.as_bytes())
-            .expect("write embedded schema to temp file");
+        tmp.write_all(json_schema().as_bytes())
+            .expect("write generated schema to temp file");
         tmp.flush().expect("flush temp schema file");
 
         let manifest_dir = env!("CARGO_MANIFEST_DIR");
@@ -1,13 +1,58 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use std::path::Path;
 
 use super::types::Schema;
 
-/// Parse a YAML schema file from the given path.
+/// The on-disk encoding of a schema file. Detected from a file's extension
+/// by default, with an explicit override available wherever a format is
+/// accepted (e.g. `envgen init --format toml`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl SchemaFormat {
+    /// Detects the format from `path`'s extension, defaulting to YAML for
+    /// `.yaml`/`.yml` or anything unrecognized.
+    pub fn from_extension(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => SchemaFormat::Toml,
+            Some("json") => SchemaFormat::Json,
+            _ => SchemaFormat::Yaml,
+        }
+    }
+
+    /// The canonical file extension for this format (no leading dot).
+    pub fn extension(self) -> &'static str {
+        match self {
+            SchemaFormat::Yaml => "yaml",
+            SchemaFormat::Toml => "toml",
+            SchemaFormat::Json => "json",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "yaml" | "yml" => Ok(SchemaFormat::Yaml),
+            "toml" => Ok(SchemaFormat::Toml),
+            "json" => Ok(SchemaFormat::Json),
+            _ => bail!("Unknown schema format: \"{}\". Expected \"yaml\", \"toml\", or \"json\".", s),
+        }
+    }
+}
+
+/// Parse a schema file from the given path, dispatching to the parser for
+/// its detected [`SchemaFormat`].
 pub fn parse_schema_file(path: &Path) -> Result<Schema> {
     let contents = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read schema file: {}", path.display()))?;
-    parse_schema(&contents)
+    match SchemaFormat::from_extension(path) {
+        SchemaFormat::Yaml => parse_schema(&contents),
+        SchemaFormat::Toml => parse_schema_toml(&contents),
+        SchemaFormat::Json => parse_schema_json(&contents),
+    }
 }
 
 /// Parse a YAML schema from a string.
@@ -16,6 +61,32 @@ pub fn parse_schema(yaml: &str) -> Result<Schema> {
     Ok(schema)
 }
 
+/// Parse a TOML schema from a string.
+pub fn parse_schema_toml(toml: &str) -> Result<Schema> {
+    let schema: Schema = toml::from_str(toml).context("Failed to parse schema TOML")?;
+    Ok(schema)
+}
+
+/// Parse a JSON schema from a string.
+pub fn parse_schema_json(json: &str) -> Result<Schema> {
+    let schema: Schema = serde_json::from_str(json).context("Failed to parse schema JSON")?;
+    Ok(schema)
+}
+
+/// Serialize `schema` to its textual representation in `format`.
+pub fn serialize_schema(schema: &Schema, format: SchemaFormat) -> Result<String> {
+    match format {
+        SchemaFormat::Yaml => {
+            serde_yaml::to_string(schema).context("Failed to serialize schema to YAML")
+        }
+        SchemaFormat::Toml => {
+            toml::to_string_pretty(schema).context("Failed to serialize schema to TOML")
+        }
+        SchemaFormat::Json => serde_json::to_string_pretty(schema)
+            .context("Failed to serialize schema to JSON"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,4 +124,59 @@ variables:
         let yaml = "not: valid: yaml: [";
         assert!(parse_schema(yaml).is_err());
     }
+
+    #[test]
+    fn test_format_from_extension() {
+        assert_eq!(
+            SchemaFormat::from_extension(Path::new("env.dev.toml")),
+            SchemaFormat::Toml
+        );
+        assert_eq!(
+            SchemaFormat::from_extension(Path::new("env.dev.json")),
+            SchemaFormat::Json
+        );
+        assert_eq!(
+            SchemaFormat::from_extension(Path::new("env.dev.yaml")),
+            SchemaFormat::Yaml
+        );
+        assert_eq!(
+            SchemaFormat::from_extension(Path::new("env.dev")),
+            SchemaFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_through_toml_and_json() {
+        let yaml = r#"
+schema_version: "1"
+metadata:
+  description: "Test schema"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test-project"
+sources:
+  test-source:
+    command: "echo {key}"
+variables:
+  MY_VAR:
+    description: "A test variable"
+    sensitive: false
+    source: static
+    values:
+      local: "hello"
+"#;
+        let schema = parse_schema(yaml).unwrap();
+
+        let toml = serialize_schema(&schema, SchemaFormat::Toml).unwrap();
+        let from_toml = parse_schema_toml(&toml).unwrap();
+        assert_eq!(from_toml.variables.len(), 1);
+        assert!(from_toml.variables.contains_key("MY_VAR"));
+
+        let json = serialize_schema(&schema, SchemaFormat::Json).unwrap();
+        let from_json = parse_schema_json(&json).unwrap();
+        assert_eq!(from_json.variables.len(), 1);
+        assert!(from_json.variables.contains_key("MY_VAR"));
+    }
 }
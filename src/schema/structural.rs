@@ -1,10 +1,13 @@
 use anyhow::{bail, Context, Result};
 use regex::Regex;
+use serde::Serialize;
 use serde_json::Value;
-use std::collections::{BTreeMap, BTreeSet};
-use std::sync::OnceLock;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::{OnceLock, RwLock};
 
 static ROOT_SCHEMA: OnceLock<Value> = OnceLock::new();
+static COMPILED_SCHEMA: OnceLock<CompiledSchema> = OnceLock::new();
 
 #[derive(Clone, Default)]
 struct JsonPointer(String);
@@ -36,13 +39,15 @@ impl JsonPointer {
 #[derive(Clone)]
 struct StructuralError {
     instance_path: JsonPointer,
+    schema_path: JsonPointer,
     message: String,
 }
 
 impl StructuralError {
-    fn new(instance_path: JsonPointer, message: impl Into<String>) -> Self {
+    fn new(instance_path: JsonPointer, schema_path: JsonPointer, message: impl Into<String>) -> Self {
         Self {
             instance_path,
+            schema_path,
             message: message.into(),
         }
     }
@@ -62,132 +67,576 @@ impl std::fmt::Display for StructuralError {
     }
 }
 
+/// The standard Draft 2020-12 "basic" output format: a top-level validity flag
+/// plus one record per failure, each naming where in the instance it failed
+/// and which schema keyword rejected it.
+#[derive(Debug, Serialize)]
+pub struct BasicOutput {
+    pub valid: bool,
+    pub errors: Vec<BasicOutputError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BasicOutputError {
+    #[serde(rename = "instanceLocation")]
+    pub instance_location: String,
+    #[serde(rename = "keywordLocation")]
+    pub keyword_location: String,
+    pub error: String,
+}
+
 fn root_schema() -> Result<&'static Value> {
     if let Some(schema) = ROOT_SCHEMA.get() {
         return Ok(schema);
     }
 
-    let parsed: Value =
-        serde_json::from_str(super::JSON_SCHEMA).context("Embedded JSON Schema is invalid JSON")?;
+    let parsed: Value = serde_json::from_str(super::json_schema())
+        .context("Generated JSON Schema is invalid JSON")?;
     let _ = ROOT_SCHEMA.set(parsed);
 
     Ok(ROOT_SCHEMA.get().expect("ROOT_SCHEMA must be initialized"))
 }
 
+// --- Compiled validator tree -----------------------------------------------
+//
+// The embedded schema is compiled once (cached in `COMPILED_SCHEMA`) into an
+// arena of `CompiledNode`s addressed by index. Compiling resolves `$ref`
+// targets to node indices, precompiles every `pattern`/`patternProperties`
+// regex, and lowers `type` down to a small bitset, so validating an instance
+// never re-parses the schema or recompiles a regex on the hot path.
+
+const TYPE_NULL: u8 = 1 << 0;
+const TYPE_BOOLEAN: u8 = 1 << 1;
+const TYPE_NUMBER: u8 = 1 << 2;
+const TYPE_INTEGER: u8 = 1 << 3;
+const TYPE_STRING: u8 = 1 << 4;
+const TYPE_ARRAY: u8 = 1 << 5;
+const TYPE_OBJECT: u8 = 1 << 6;
+
+fn type_bit(name: &str) -> u8 {
+    match name {
+        "null" => TYPE_NULL,
+        "boolean" => TYPE_BOOLEAN,
+        "number" => TYPE_NUMBER,
+        "integer" => TYPE_INTEGER,
+        "string" => TYPE_STRING,
+        "array" => TYPE_ARRAY,
+        "object" => TYPE_OBJECT,
+        _ => 0,
+    }
+}
+
+/// Lower a schema `type` keyword value to a bitset. An unrecognized shape
+/// (neither a string nor an array, which is invalid but not our job to
+/// reject here) matches anything.
+fn compute_type_bits(expected: &Value) -> u8 {
+    match expected {
+        Value::String(t) => type_bit(t),
+        Value::Array(types) => types
+            .iter()
+            .filter_map(|v| v.as_str())
+            .fold(0u8, |acc, t| acc | type_bit(t)),
+        _ => u8::MAX,
+    }
+}
+
+fn instance_type_bits(instance: &Value) -> u8 {
+    match instance {
+        Value::Null => TYPE_NULL,
+        Value::Bool(_) => TYPE_BOOLEAN,
+        Value::Number(n) => {
+            let mut bits = TYPE_NUMBER;
+            if n.is_i64() || n.is_u64() {
+                bits |= TYPE_INTEGER;
+            }
+            bits
+        }
+        Value::String(_) => TYPE_STRING,
+        Value::Array(_) => TYPE_ARRAY,
+        Value::Object(_) => TYPE_OBJECT,
+    }
+}
+
+#[derive(Default)]
+enum AdditionalPropertiesNode {
+    #[default]
+    Allow,
+    Deny,
+    Schema(usize),
+}
+
+/// One compiled node in the validator tree, addressed by its index in
+/// `CompiledSchema::arena`. Mirrors the keywords `validate_node` understands;
+/// everything here is resolved once at compile time instead of being
+/// re-derived from the raw `Value` on every validation call.
+#[derive(Default)]
+struct CompiledNode {
+    /// Canonical JSON Pointer of this schema node within the embedded schema,
+    /// used to build `keywordLocation`s without re-threading a path param.
+    pointer: String,
+
+    /// `true`/`false` schemas short-circuit everything else.
+    bool_schema: Option<bool>,
+
+    ref_target: Option<usize>,
+
+    all_of: Vec<usize>,
+    any_of: Vec<usize>,
+    one_of: Vec<usize>,
+    not: Option<usize>,
+
+    if_: Option<usize>,
+    then_: Option<usize>,
+    else_: Option<usize>,
+
+    type_bits: Option<u8>,
+    type_display: Option<String>,
+
+    const_value: Option<Value>,
+    enum_values: Option<Vec<Value>>,
+
+    maximum: Option<f64>,
+    minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    multiple_of: Option<f64>,
+
+    min_length: Option<u64>,
+    max_length: Option<u64>,
+    pattern: Option<(Regex, String)>,
+    format: Option<String>,
+
+    min_properties: Option<u64>,
+    max_properties: Option<u64>,
+    required: Vec<String>,
+    property_names: Option<usize>,
+    properties: BTreeMap<String, usize>,
+    pattern_properties: Vec<(Regex, String, usize)>,
+    additional_properties: Option<AdditionalPropertiesNode>,
+    dependent_required: BTreeMap<String, Vec<String>>,
+    dependent_schemas: BTreeMap<String, usize>,
+
+    min_items: Option<u64>,
+    max_items: Option<u64>,
+    unique_items: bool,
+    prefix_items: Vec<usize>,
+    items: Option<usize>,
+    contains: Option<usize>,
+    min_contains: Option<u64>,
+    max_contains: Option<u64>,
+}
+
+impl CompiledNode {
+    fn keyword_path(&self, keyword: &str) -> JsonPointer {
+        JsonPointer(format!("{}/{}", self.pointer, keyword))
+    }
+}
+
+struct CompiledSchema {
+    arena: Vec<CompiledNode>,
+    root: usize,
+}
+
+/// Walks the raw schema `Value` once, compiling it into an arena of
+/// `CompiledNode`s. Sub-schemas are cached by their JSON Pointer so that a
+/// `$ref` resolves to (and shares) the same compiled node as the location it
+/// points at, and so that self-referential schemas don't recurse forever.
+struct Compiler<'a> {
+    root: &'a Value,
+    arena: Vec<CompiledNode>,
+    cache: HashMap<String, usize>,
+}
+
+impl<'a> Compiler<'a> {
+    fn new(root: &'a Value) -> Self {
+        Self {
+            root,
+            arena: Vec::new(),
+            cache: HashMap::new(),
+        }
+    }
+
+    fn compile_at(&mut self, schema: &Value, pointer: &str) -> Result<usize> {
+        if let Some(&idx) = self.cache.get(pointer) {
+            return Ok(idx);
+        }
+
+        let idx = self.arena.len();
+        self.arena.push(CompiledNode::default());
+        self.cache.insert(pointer.to_string(), idx);
+
+        let mut node = self.build_node(schema, pointer)?;
+        node.pointer = pointer.to_string();
+        self.arena[idx] = node;
+
+        Ok(idx)
+    }
+
+    fn build_node(&mut self, schema: &Value, pointer: &str) -> Result<CompiledNode> {
+        let mut node = CompiledNode::default();
+
+        if let Value::Bool(b) = schema {
+            node.bool_schema = Some(*b);
+            return Ok(node);
+        }
+
+        let schema_obj = match schema.as_object() {
+            Some(o) => o,
+            None => return Ok(node),
+        };
+
+        if let Some(Value::String(r)) = schema_obj.get("$ref") {
+            let (target, target_pointer) = resolve_ref(self.root, r)
+                .with_context(|| format!("Unsupported or unresolved $ref: \"{}\"", r))?;
+            node.ref_target = Some(self.compile_at(target, &target_pointer)?);
+        }
+
+        if let Some(Value::Array(all_of)) = schema_obj.get("allOf") {
+            for (idx, sub) in all_of.iter().enumerate() {
+                let sub_pointer = format!("{}/allOf/{}", pointer, idx);
+                node.all_of.push(self.compile_at(sub, &sub_pointer)?);
+            }
+        }
+
+        if let Some(Value::Array(any_of)) = schema_obj.get("anyOf") {
+            for (idx, sub) in any_of.iter().enumerate() {
+                let sub_pointer = format!("{}/anyOf/{}", pointer, idx);
+                node.any_of.push(self.compile_at(sub, &sub_pointer)?);
+            }
+        }
+
+        if let Some(Value::Array(one_of)) = schema_obj.get("oneOf") {
+            for (idx, sub) in one_of.iter().enumerate() {
+                let sub_pointer = format!("{}/oneOf/{}", pointer, idx);
+                node.one_of.push(self.compile_at(sub, &sub_pointer)?);
+            }
+        }
+
+        if let Some(not_schema) = schema_obj.get("not") {
+            let sub_pointer = format!("{}/not", pointer);
+            node.not = Some(self.compile_at(not_schema, &sub_pointer)?);
+        }
+
+        if let Some(if_schema) = schema_obj.get("if") {
+            let sub_pointer = format!("{}/if", pointer);
+            node.if_ = Some(self.compile_at(if_schema, &sub_pointer)?);
+        }
+        if let Some(then_schema) = schema_obj.get("then") {
+            let sub_pointer = format!("{}/then", pointer);
+            node.then_ = Some(self.compile_at(then_schema, &sub_pointer)?);
+        }
+        if let Some(else_schema) = schema_obj.get("else") {
+            let sub_pointer = format!("{}/else", pointer);
+            node.else_ = Some(self.compile_at(else_schema, &sub_pointer)?);
+        }
+
+        if let Some(expected_types) = schema_obj.get("type") {
+            node.type_bits = Some(compute_type_bits(expected_types));
+            node.type_display = Some(schema_type_display(expected_types));
+        }
+
+        node.const_value = schema_obj.get("const").cloned();
+        if let Some(Value::Array(values)) = schema_obj.get("enum") {
+            node.enum_values = Some(values.clone());
+        }
+
+        node.maximum = schema_obj.get("maximum").and_then(|v| v.as_f64());
+        node.minimum = schema_obj.get("minimum").and_then(|v| v.as_f64());
+        node.exclusive_maximum = schema_obj.get("exclusiveMaximum").and_then(|v| v.as_f64());
+        node.exclusive_minimum = schema_obj.get("exclusiveMinimum").and_then(|v| v.as_f64());
+        node.multiple_of = schema_obj.get("multipleOf").and_then(|v| v.as_f64());
+
+        node.min_length = schema_obj.get("minLength").and_then(|v| v.as_u64());
+        node.max_length = schema_obj.get("maxLength").and_then(|v| v.as_u64());
+
+        if let Some(Value::String(pattern)) = schema_obj.get("pattern") {
+            let re = Regex::new(pattern)
+                .with_context(|| format!("Invalid regex pattern in schema: {}", pattern))?;
+            node.pattern = Some((re, pattern.clone()));
+        }
+
+        if let Some(Value::String(format_name)) = schema_obj.get("format") {
+            node.format = Some(format_name.clone());
+        }
+
+        node.min_properties = schema_obj.get("minProperties").and_then(|v| v.as_u64());
+        node.max_properties = schema_obj.get("maxProperties").and_then(|v| v.as_u64());
+
+        if let Some(Value::Array(required)) = schema_obj.get("required") {
+            node.required = required
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+        }
+
+        if let Some(property_names_schema) = schema_obj.get("propertyNames") {
+            let sub_pointer = format!("{}/propertyNames", pointer);
+            node.property_names = Some(self.compile_at(property_names_schema, &sub_pointer)?);
+        }
+
+        if let Some(props) = schema_obj.get("properties").and_then(|v| v.as_object()) {
+            for (prop, sub_schema) in props {
+                let sub_pointer =
+                    format!("{}/properties/{}", pointer, escape_pointer_segment(prop));
+                let idx = self.compile_at(sub_schema, &sub_pointer)?;
+                node.properties.insert(prop.clone(), idx);
+            }
+        }
+
+        if let Some(map) = schema_obj
+            .get("patternProperties")
+            .and_then(|v| v.as_object())
+        {
+            for (pattern, sub_schema) in map {
+                let re = Regex::new(pattern)
+                    .with_context(|| format!("Invalid regex in patternProperties: {}", pattern))?;
+                let sub_pointer = format!(
+                    "{}/patternProperties/{}",
+                    pointer,
+                    escape_pointer_segment(pattern)
+                );
+                let idx = self.compile_at(sub_schema, &sub_pointer)?;
+                node.pattern_properties.push((re, pattern.clone(), idx));
+            }
+        }
+
+        if let Some(additional) = schema_obj.get("additionalProperties") {
+            node.additional_properties = Some(match additional {
+                Value::Bool(false) => AdditionalPropertiesNode::Deny,
+                Value::Bool(true) => AdditionalPropertiesNode::Allow,
+                Value::Object(_) => {
+                    let sub_pointer = format!("{}/additionalProperties", pointer);
+                    AdditionalPropertiesNode::Schema(self.compile_at(additional, &sub_pointer)?)
+                }
+                _ => bail!("Unsupported additionalProperties value in embedded schema"),
+            });
+        }
+
+        if let Some(Value::Object(dependent_required)) = schema_obj.get("dependentRequired") {
+            for (trigger_prop, required) in dependent_required {
+                if let Value::Array(required) = required {
+                    node.dependent_required.insert(
+                        trigger_prop.clone(),
+                        required
+                            .iter()
+                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                            .collect(),
+                    );
+                }
+            }
+        }
+
+        if let Some(Value::Object(dependent_schemas)) = schema_obj.get("dependentSchemas") {
+            for (trigger_prop, sub_schema) in dependent_schemas {
+                let sub_pointer = format!(
+                    "{}/dependentSchemas/{}",
+                    pointer,
+                    escape_pointer_segment(trigger_prop)
+                );
+                let idx = self.compile_at(sub_schema, &sub_pointer)?;
+                node.dependent_schemas.insert(trigger_prop.clone(), idx);
+            }
+        }
+
+        node.min_items = schema_obj.get("minItems").and_then(|v| v.as_u64());
+        node.max_items = schema_obj.get("maxItems").and_then(|v| v.as_u64());
+        node.unique_items = schema_obj
+            .get("uniqueItems")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if let Some(Value::Array(prefix_items)) = schema_obj.get("prefixItems") {
+            for (idx, sub_schema) in prefix_items.iter().enumerate() {
+                let sub_pointer = format!("{}/prefixItems/{}", pointer, idx);
+                node.prefix_items.push(self.compile_at(sub_schema, &sub_pointer)?);
+            }
+        }
+        if let Some(items_schema) = schema_obj.get("items") {
+            let sub_pointer = format!("{}/items", pointer);
+            node.items = Some(self.compile_at(items_schema, &sub_pointer)?);
+        }
+
+        if let Some(contains_schema) = schema_obj.get("contains") {
+            let sub_pointer = format!("{}/contains", pointer);
+            node.contains = Some(self.compile_at(contains_schema, &sub_pointer)?);
+            node.min_contains = schema_obj.get("minContains").and_then(|v| v.as_u64());
+            node.max_contains = schema_obj.get("maxContains").and_then(|v| v.as_u64());
+        }
+
+        Ok(node)
+    }
+}
+
+fn compiled_schema() -> Result<&'static CompiledSchema> {
+    if let Some(compiled) = COMPILED_SCHEMA.get() {
+        return Ok(compiled);
+    }
+
+    let root = root_schema()?;
+    let mut compiler = Compiler::new(root);
+    let root_idx = compiler.compile_at(root, "")?;
+    let compiled = CompiledSchema {
+        arena: compiler.arena,
+        root: root_idx,
+    };
+    let _ = COMPILED_SCHEMA.set(compiled);
+
+    Ok(COMPILED_SCHEMA
+        .get()
+        .expect("COMPILED_SCHEMA must be initialized"))
+}
+
 pub fn validate_instance(instance: &Value) -> Result<Vec<String>> {
-    let schema = root_schema()?;
-    let mut errors = Vec::new();
-    validate_schema(schema, instance, schema, &JsonPointer::root(), &mut errors)?;
+    let errors = collect_errors(instance)?;
     Ok(errors.into_iter().map(|e| e.to_string()).collect())
 }
 
-fn validate_schema(
-    schema: &Value,
+/// Validate an instance and return the standard Draft 2020-12 "basic" output,
+/// suitable for editors/CI to consume programmatically instead of scraping text.
+pub fn validate_instance_basic(instance: &Value) -> Result<BasicOutput> {
+    let errors = collect_errors(instance)?;
+    let valid = errors.is_empty();
+    Ok(BasicOutput {
+        valid,
+        errors: errors
+            .into_iter()
+            .map(|e| BasicOutputError {
+                instance_location: e.instance_path.as_str().to_string(),
+                keyword_location: e.schema_path.as_str().to_string(),
+                error: e.message,
+            })
+            .collect(),
+    })
+}
+
+fn collect_errors(instance: &Value) -> Result<Vec<StructuralError>> {
+    let tree = compiled_schema()?;
+    let mut errors = Vec::new();
+    validate_node(tree, tree.root, instance, &JsonPointer::root(), &mut errors)?;
+    Ok(errors)
+}
+
+fn validate_node(
+    tree: &CompiledSchema,
+    node_idx: usize,
     instance: &Value,
-    root: &Value,
     instance_path: &JsonPointer,
     errors: &mut Vec<StructuralError>,
 ) -> Result<()> {
-    // Boolean schemas (Draft 2020-12)
-    if let Value::Bool(b) = schema {
-        if *b {
-            return Ok(());
+    let node = &tree.arena[node_idx];
+
+    if let Some(b) = node.bool_schema {
+        if !b {
+            errors.push(StructuralError::new(
+                instance_path.clone(),
+                JsonPointer(node.pointer.clone()),
+                "does not match schema (false)",
+            ));
         }
-        errors.push(StructuralError::new(
-            instance_path.clone(),
-            "does not match schema (false)",
-        ));
         return Ok(());
     }
 
-    let schema_obj = match schema.as_object() {
-        Some(o) => o,
-        None => return Ok(()),
-    };
-
-    // $ref (local refs only)
-    if let Some(Value::String(r)) = schema_obj.get("$ref") {
-        let target = resolve_ref(root, r)
-            .with_context(|| format!("Unsupported or unresolved $ref: \"{}\"", r))?;
-        validate_schema(target, instance, root, instance_path, errors)?;
+    if let Some(target) = node.ref_target {
+        validate_node(tree, target, instance, instance_path, errors)?;
     }
 
-    // allOf / anyOf / oneOf / not
-    if let Some(Value::Array(all_of)) = schema_obj.get("allOf") {
-        for sub in all_of {
-            validate_schema(sub, instance, root, instance_path, errors)?;
-        }
+    for &idx in &node.all_of {
+        validate_node(tree, idx, instance, instance_path, errors)?;
     }
 
-    if let Some(Value::Array(any_of)) = schema_obj.get("anyOf") {
+    if !node.any_of.is_empty() {
         let mut any_pass = false;
-        for sub in any_of {
+        let mut branch_errors: Vec<Vec<StructuralError>> = Vec::with_capacity(node.any_of.len());
+        for &idx in &node.any_of {
             let mut sub_errors = Vec::new();
-            validate_schema(sub, instance, root, instance_path, &mut sub_errors)?;
+            validate_node(tree, idx, instance, instance_path, &mut sub_errors)?;
             if sub_errors.is_empty() {
                 any_pass = true;
                 break;
             }
+            branch_errors.push(sub_errors);
         }
         if !any_pass {
             errors.push(StructuralError::new(
                 instance_path.clone(),
-                "must match at least one schema in anyOf",
+                node.keyword_path("anyOf"),
+                format!(
+                    "must match at least one schema in anyOf ({})",
+                    describe_closest_branch(&branch_errors)
+                ),
             ));
         }
     }
 
-    if let Some(Value::Array(one_of)) = schema_obj.get("oneOf") {
-        let mut pass_count = 0usize;
-        for sub in one_of {
+    if !node.one_of.is_empty() {
+        let mut matched_indices: Vec<usize> = Vec::new();
+        let mut branch_errors: Vec<Vec<StructuralError>> = Vec::with_capacity(node.one_of.len());
+        for (idx, &branch_idx) in node.one_of.iter().enumerate() {
             let mut sub_errors = Vec::new();
-            validate_schema(sub, instance, root, instance_path, &mut sub_errors)?;
+            validate_node(tree, branch_idx, instance, instance_path, &mut sub_errors)?;
             if sub_errors.is_empty() {
-                pass_count += 1;
+                matched_indices.push(idx);
             }
+            branch_errors.push(sub_errors);
         }
-        if pass_count != 1 {
+        if matched_indices.len() > 1 {
+            errors.push(StructuralError::new(
+                instance_path.clone(),
+                node.keyword_path("oneOf"),
+                format!(
+                    "must match exactly one schema in oneOf, but matched branches {}",
+                    matched_indices
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+        } else if matched_indices.is_empty() {
             errors.push(StructuralError::new(
                 instance_path.clone(),
-                "must match exactly one schema in oneOf",
+                node.keyword_path("oneOf"),
+                format!(
+                    "must match exactly one schema in oneOf ({})",
+                    describe_closest_branch(&branch_errors)
+                ),
             ));
         }
     }
 
-    if let Some(not_schema) = schema_obj.get("not") {
+    if let Some(not_idx) = node.not {
         let mut sub_errors = Vec::new();
-        validate_schema(not_schema, instance, root, instance_path, &mut sub_errors)?;
+        validate_node(tree, not_idx, instance, instance_path, &mut sub_errors)?;
         if sub_errors.is_empty() {
             errors.push(StructuralError::new(
                 instance_path.clone(),
-                "must not match schema in not",
+                node.keyword_path("not"),
+                format!("must not match schema in not, but value is {}", instance),
             ));
         }
     }
 
-    // if / then / else
-    if let Some(if_schema) = schema_obj.get("if") {
+    if let Some(if_idx) = node.if_ {
         let mut if_errors = Vec::new();
-        validate_schema(if_schema, instance, root, instance_path, &mut if_errors)?;
-        let if_passes = if_errors.is_empty();
-
-        if if_passes {
-            if let Some(then_schema) = schema_obj.get("then") {
-                validate_schema(then_schema, instance, root, instance_path, errors)?;
+        validate_node(tree, if_idx, instance, instance_path, &mut if_errors)?;
+        if if_errors.is_empty() {
+            if let Some(then_idx) = node.then_ {
+                validate_node(tree, then_idx, instance, instance_path, errors)?;
             }
-        } else if let Some(else_schema) = schema_obj.get("else") {
-            validate_schema(else_schema, instance, root, instance_path, errors)?;
+        } else if let Some(else_idx) = node.else_ {
+            validate_node(tree, else_idx, instance, instance_path, errors)?;
         }
     }
 
     // type check (if present). If it fails, stop evaluating other type-specific keywords.
-    if let Some(expected_types) = schema_obj.get("type") {
-        if !type_matches(expected_types, instance) {
+    if let Some(expected_bits) = node.type_bits {
+        if instance_type_bits(instance) & expected_bits == 0 {
             errors.push(StructuralError::new(
                 instance_path.clone(),
+                node.keyword_path("type"),
                 format!(
                     "expected type {}, got {}",
-                    schema_type_display(expected_types),
+                    node.type_display.as_deref().unwrap_or("<unknown>"),
                     instance_type_display(instance)
                 ),
             ));
@@ -196,154 +645,281 @@ fn validate_schema(
     }
 
     // const / enum
-    if let Some(const_value) = schema_obj.get("const") {
+    if let Some(const_value) = &node.const_value {
         if instance != const_value {
             errors.push(StructuralError::new(
                 instance_path.clone(),
+                node.keyword_path("const"),
                 format!("must be equal to {}", const_value),
             ));
         }
     }
 
-    if let Some(Value::Array(enum_values)) = schema_obj.get("enum") {
+    if let Some(enum_values) = &node.enum_values {
         if !enum_values.iter().any(|v| v == instance) {
             errors.push(StructuralError::new(
                 instance_path.clone(),
+                node.keyword_path("enum"),
                 "must be one of the allowed values",
             ));
         }
     }
 
+    // number keywords
+    if instance.is_number() {
+        let n = instance.as_f64().context("Numeric instance is not representable as f64")?;
+
+        if let Some(maximum) = node.maximum {
+            if n > maximum {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("maximum"),
+                    format!("must be <= {}", maximum),
+                ));
+            }
+        }
+
+        if let Some(minimum) = node.minimum {
+            if n < minimum {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("minimum"),
+                    format!("must be >= {}", minimum),
+                ));
+            }
+        }
+
+        if let Some(exclusive_max) = node.exclusive_maximum {
+            if n >= exclusive_max {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("exclusiveMaximum"),
+                    format!("must be < {}", exclusive_max),
+                ));
+            }
+        }
+
+        if let Some(exclusive_min) = node.exclusive_minimum {
+            if n <= exclusive_min {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("exclusiveMinimum"),
+                    format!("must be > {}", exclusive_min),
+                ));
+            }
+        }
+
+        if let Some(multiple_of) = node.multiple_of {
+            if !is_multiple_of(n, multiple_of) {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("multipleOf"),
+                    format!("must be a multiple of {}", multiple_of),
+                ));
+            }
+        }
+    }
+
     // string keywords
     if let Some(s) = instance.as_str() {
-        if let Some(min_len) = schema_obj.get("minLength").and_then(|v| v.as_u64()) {
+        if let Some(min_len) = node.min_length {
             if (s.chars().count() as u64) < min_len {
                 errors.push(StructuralError::new(
                     instance_path.clone(),
+                    node.keyword_path("minLength"),
                     format!("string must be at least {} characters", min_len),
                 ));
             }
         }
 
-        if let Some(Value::String(pattern)) = schema_obj.get("pattern") {
-            let re = Regex::new(pattern)
-                .with_context(|| format!("Invalid regex pattern in schema: {}", pattern))?;
+        if let Some(max_len) = node.max_length {
+            if (s.chars().count() as u64) > max_len {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("maxLength"),
+                    format!("string must be at most {} characters", max_len),
+                ));
+            }
+        }
+
+        if let Some((re, pattern)) = &node.pattern {
             if !re.is_match(s) {
                 errors.push(StructuralError::new(
                     instance_path.clone(),
+                    node.keyword_path("pattern"),
                     format!("string does not match pattern {}", pattern),
                 ));
             }
         }
+
+        if let Some(format_name) = &node.format {
+            let registry = format_registry()
+                .read()
+                .expect("format registry lock poisoned");
+            if let Some(checker) = registry.get(format_name.as_str()) {
+                if !checker(s) {
+                    errors.push(StructuralError::new(
+                        instance_path.clone(),
+                        node.keyword_path("format"),
+                        format!("string does not match format \"{}\"", format_name),
+                    ));
+                }
+            }
+        }
     }
 
     // object keywords
     if let Some(obj) = instance.as_object() {
-        if let Some(min_props) = schema_obj.get("minProperties").and_then(|v| v.as_u64()) {
+        if let Some(min_props) = node.min_properties {
             if (obj.len() as u64) < min_props {
                 errors.push(StructuralError::new(
                     instance_path.clone(),
+                    node.keyword_path("minProperties"),
                     format!("object must have at least {} properties", min_props),
                 ));
             }
         }
 
-        if let Some(Value::Array(required)) = schema_obj.get("required") {
-            for prop in required.iter().filter_map(|v| v.as_str()) {
-                if !obj.contains_key(prop) {
-                    errors.push(StructuralError::new(
-                        instance_path.clone(),
-                        format!("missing required property \"{}\"", prop),
-                    ));
-                }
+        if let Some(max_props) = node.max_properties {
+            if (obj.len() as u64) > max_props {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("maxProperties"),
+                    format!("object must have at most {} properties", max_props),
+                ));
+            }
+        }
+
+        for prop in &node.required {
+            if !obj.contains_key(prop) {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("required"),
+                    format!("missing required property \"{}\"", prop),
+                ));
             }
         }
 
         // propertyNames
-        if let Some(property_names_schema) = schema_obj.get("propertyNames") {
+        if let Some(property_names_idx) = node.property_names {
             for key in sorted_object_keys(obj) {
                 let key_value = Value::String(key.clone());
                 let key_path = instance_path.push_prop(&key);
-                validate_schema(property_names_schema, &key_value, root, &key_path, errors)?;
+                validate_node(tree, property_names_idx, &key_value, &key_path, errors)?;
             }
         }
 
         // properties
-        let properties = schema_obj
-            .get("properties")
-            .and_then(|v| v.as_object())
-            .map(|m| m.iter().collect::<BTreeMap<_, _>>());
+        for (prop, &prop_idx) in &node.properties {
+            if let Some(value) = obj.get(prop) {
+                let next_instance_path = instance_path.push_prop(prop);
+                validate_node(tree, prop_idx, value, &next_instance_path, errors)?;
+            }
+        }
 
-        if let Some(props) = &properties {
-            for (prop, prop_schema) in props {
-                if let Some(value) = obj.get(*prop) {
-                    let next_path = instance_path.push_prop(prop);
-                    validate_schema(prop_schema, value, root, &next_path, errors)?;
+        // patternProperties: remember which keys matched so additionalProperties
+        // can treat them as known.
+        let mut pattern_matched_keys: BTreeSet<String> = BTreeSet::new();
+        for key in sorted_object_keys(obj) {
+            for (re, _pattern, sub_idx) in &node.pattern_properties {
+                if re.is_match(&key) {
+                    pattern_matched_keys.insert(key.clone());
+                    let next_instance_path = instance_path.push_prop(&key);
+                    validate_node(tree, *sub_idx, obj.get(&key).unwrap(), &next_instance_path, errors)?;
                 }
             }
         }
 
         // additionalProperties
-        if let Some(additional) = schema_obj.get("additionalProperties") {
-            let known_props: BTreeSet<String> = properties
-                .as_ref()
-                .map(|m| m.keys().map(|k| (*k).to_string()).collect())
-                .unwrap_or_default();
+        if let Some(additional) = &node.additional_properties {
+            let known_props: BTreeSet<String> = node
+                .properties
+                .keys()
+                .cloned()
+                .chain(pattern_matched_keys)
+                .collect();
 
             match additional {
-                Value::Bool(false) => {
+                AdditionalPropertiesNode::Deny => {
                     for key in sorted_object_keys(obj) {
                         if !known_props.contains(&key) {
                             errors.push(StructuralError::new(
                                 instance_path.push_prop(&key),
+                                node.keyword_path("additionalProperties"),
                                 "unknown property",
                             ));
                         }
                     }
                 }
-                Value::Object(_) | Value::Bool(true) => {
+                AdditionalPropertiesNode::Schema(sub_idx) => {
                     for key in sorted_object_keys(obj) {
                         if known_props.contains(&key) {
                             continue;
                         }
-                        let next_path = instance_path.push_prop(&key);
-                        validate_schema(
-                            additional,
-                            obj.get(&key).unwrap(),
-                            root,
-                            &next_path,
-                            errors,
-                        )?;
+                        let next_instance_path = instance_path.push_prop(&key);
+                        validate_node(tree, *sub_idx, obj.get(&key).unwrap(), &next_instance_path, errors)?;
                     }
                 }
-                _ => {
-                    bail!("Unsupported additionalProperties value in embedded schema");
+                AdditionalPropertiesNode::Allow => {}
+            }
+        }
+
+        // dependentRequired
+        for (trigger_prop, required) in &node.dependent_required {
+            if !obj.contains_key(trigger_prop) {
+                continue;
+            }
+            for prop in required {
+                if !obj.contains_key(prop) {
+                    errors.push(StructuralError::new(
+                        instance_path.clone(),
+                        node.keyword_path(&format!("dependentRequired/{}", trigger_prop)),
+                        format!(
+                            "property \"{}\" requires \"{}\" to also be present",
+                            trigger_prop, prop
+                        ),
+                    ));
                 }
             }
         }
+
+        // dependentSchemas
+        for (trigger_prop, &sub_idx) in &node.dependent_schemas {
+            if obj.contains_key(trigger_prop) {
+                validate_node(tree, sub_idx, instance, instance_path, errors)?;
+            }
+        }
     }
 
     // array keywords
     if let Some(arr) = instance.as_array() {
-        if let Some(min_items) = schema_obj.get("minItems").and_then(|v| v.as_u64()) {
+        if let Some(min_items) = node.min_items {
             if (arr.len() as u64) < min_items {
                 errors.push(StructuralError::new(
                     instance_path.clone(),
+                    node.keyword_path("minItems"),
                     format!("array must have at least {} items", min_items),
                 ));
             }
         }
 
-        if schema_obj
-            .get("uniqueItems")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false)
-        {
+        if let Some(max_items) = node.max_items {
+            if (arr.len() as u64) > max_items {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("maxItems"),
+                    format!("array must have at most {} items", max_items),
+                ));
+            }
+        }
+
+        if node.unique_items {
             for i in 0..arr.len() {
                 for j in (i + 1)..arr.len() {
                     if arr[i] == arr[j] {
                         errors.push(StructuralError::new(
                             instance_path.clone(),
+                            node.keyword_path("uniqueItems"),
                             "array items must be unique",
                         ));
                         break;
@@ -352,10 +928,62 @@ fn validate_schema(
             }
         }
 
-        if let Some(items_schema) = schema_obj.get("items") {
+        // prefixItems (tuple validation) + items applied to the remaining tail.
+        // Without prefixItems, items behaves as the list-validation schema applied to every element.
+        if !node.prefix_items.is_empty() {
+            for (idx, &sub_idx) in node.prefix_items.iter().enumerate() {
+                if let Some(item) = arr.get(idx) {
+                    let next_instance_path = instance_path.push_index(idx);
+                    validate_node(tree, sub_idx, item, &next_instance_path, errors)?;
+                }
+            }
+            if let Some(items_idx) = node.items {
+                for (idx, item) in arr.iter().enumerate().skip(node.prefix_items.len()) {
+                    let next_instance_path = instance_path.push_index(idx);
+                    validate_node(tree, items_idx, item, &next_instance_path, errors)?;
+                }
+            }
+        } else if let Some(items_idx) = node.items {
             for (idx, item) in arr.iter().enumerate() {
-                let next_path = instance_path.push_index(idx);
-                validate_schema(items_schema, item, root, &next_path, errors)?;
+                let next_instance_path = instance_path.push_index(idx);
+                validate_node(tree, items_idx, item, &next_instance_path, errors)?;
+            }
+        }
+
+        // contains / minContains / maxContains
+        if let Some(contains_idx) = node.contains {
+            let mut matched = 0u64;
+            for item in arr {
+                let mut sub_errors = Vec::new();
+                validate_node(tree, contains_idx, item, instance_path, &mut sub_errors)?;
+                if sub_errors.is_empty() {
+                    matched += 1;
+                }
+            }
+
+            let min_contains = node.min_contains.unwrap_or(1);
+            if matched < min_contains {
+                errors.push(StructuralError::new(
+                    instance_path.clone(),
+                    node.keyword_path("contains"),
+                    format!(
+                        "array must contain at least {} item(s) matching \"contains\", found {}",
+                        min_contains, matched
+                    ),
+                ));
+            }
+
+            if let Some(max_contains) = node.max_contains {
+                if matched > max_contains {
+                    errors.push(StructuralError::new(
+                        instance_path.clone(),
+                        node.keyword_path("contains"),
+                        format!(
+                            "array must contain at most {} item(s) matching \"contains\", found {}",
+                            max_contains, matched
+                        ),
+                    ));
+                }
             }
         }
     }
@@ -363,45 +991,50 @@ fn validate_schema(
     Ok(())
 }
 
-fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Result<&'a Value> {
+/// Describe whichever anyOf/oneOf branch came closest to passing (fewest errors),
+/// so callers get an actionable hint instead of a bare "must match one of N schemas".
+fn describe_closest_branch(branch_errors: &[Vec<StructuralError>]) -> String {
+    match branch_errors.iter().enumerate().min_by_key(|(_, errs)| errs.len()) {
+        Some((idx, errs)) if !errs.is_empty() => format!(
+            "closest match was branch {}: {}",
+            idx,
+            errs.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ")
+        ),
+        _ => "no branch details available".to_string(),
+    }
+}
+
+fn is_multiple_of(value: f64, multiple_of: f64) -> bool {
+    if multiple_of == 0.0 {
+        return false;
+    }
+    if value.fract() == 0.0 && multiple_of.fract() == 0.0 {
+        return (value as i64) % (multiple_of as i64) == 0;
+    }
+    let quotient = value / multiple_of;
+    (quotient - quotient.round()).abs() < 1e-9
+}
+
+/// Resolve a local `$ref` to its target value and the target's own JSON
+/// Pointer (used as the compiled node's cache key, so repeated `$ref`s to the
+/// same location share one compiled node).
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Result<(&'a Value, String)> {
     if !reference.starts_with('#') {
         bail!("Only local refs are supported: {}", reference);
     }
 
     let pointer = &reference[1..];
     if pointer.is_empty() {
-        return Ok(root);
+        return Ok((root, String::new()));
     }
     if !pointer.starts_with('/') {
         bail!("Invalid local ref: {}", reference);
     }
 
-    root.pointer(pointer)
-        .ok_or_else(|| anyhow::anyhow!("Unresolved $ref: {}", reference))
-}
-
-fn type_matches(expected: &Value, instance: &Value) -> bool {
-    match expected {
-        Value::String(t) => instance_type_matches(t, instance),
-        Value::Array(types) => types
-            .iter()
-            .filter_map(|v| v.as_str())
-            .any(|t| instance_type_matches(t, instance)),
-        _ => true,
-    }
-}
-
-fn instance_type_matches(t: &str, instance: &Value) -> bool {
-    match t {
-        "object" => instance.is_object(),
-        "array" => instance.is_array(),
-        "string" => instance.is_string(),
-        "boolean" => instance.is_boolean(),
-        "number" => instance.is_number(),
-        "integer" => instance.as_i64().is_some() || instance.as_u64().is_some(),
-        "null" => instance.is_null(),
-        _ => true,
-    }
+    let target = root
+        .pointer(pointer)
+        .ok_or_else(|| anyhow::anyhow!("Unresolved $ref: {}", reference))?;
+    Ok((target, pointer.to_string()))
 }
 
 fn schema_type_display(expected: &Value) -> String {
@@ -442,3 +1075,97 @@ fn sorted_object_keys(obj: &serde_json::Map<String, Value>) -> Vec<String> {
     keys.sort();
     keys
 }
+
+type FormatChecker = fn(&str) -> bool;
+
+static FORMAT_REGISTRY: OnceLock<RwLock<BTreeMap<String, FormatChecker>>> = OnceLock::new();
+
+fn format_registry() -> &'static RwLock<BTreeMap<String, FormatChecker>> {
+    FORMAT_REGISTRY.get_or_init(|| RwLock::new(default_formats()))
+}
+
+/// Register a format checker under `name`, overwriting any existing checker with that name.
+/// Lets downstream embedders assert project-specific `format` values (e.g. `secret-ref`,
+/// `semver`) that aren't among the built-ins below.
+pub fn register_format(name: &str, checker: FormatChecker) {
+    format_registry()
+        .write()
+        .expect("format registry lock poisoned")
+        .insert(name.to_string(), checker);
+}
+
+fn default_formats() -> BTreeMap<String, FormatChecker> {
+    let mut formats: BTreeMap<String, FormatChecker> = BTreeMap::new();
+    formats.insert("uri".to_string(), is_uri_format);
+    formats.insert("hostname".to_string(), is_hostname_format);
+    formats.insert("ipv4".to_string(), is_ipv4_format);
+    formats.insert("ipv6".to_string(), is_ipv6_format);
+    formats.insert("email".to_string(), is_email_format);
+    formats.insert("duration".to_string(), is_duration_format);
+    formats.insert("regex".to_string(), is_regex_format);
+    formats.insert("uuid".to_string(), is_uuid_format);
+    formats
+}
+
+fn is_uri_format(s: &str) -> bool {
+    static URI_RE: OnceLock<Regex> = OnceLock::new();
+    URI_RE
+        .get_or_init(|| Regex::new(r"^[a-zA-Z][a-zA-Z0-9+.-]*:\S+$").unwrap())
+        .is_match(s)
+}
+
+fn is_hostname_format(s: &str) -> bool {
+    static HOSTNAME_RE: OnceLock<Regex> = OnceLock::new();
+    s.len() <= 253
+        && HOSTNAME_RE
+            .get_or_init(|| {
+                Regex::new(
+                    r"^([a-zA-Z0-9]([a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?)(\.[a-zA-Z0-9]([a-zA-Z0-9-]{0,61}[a-zA-Z0-9])?)*$",
+                )
+                .unwrap()
+            })
+            .is_match(s)
+}
+
+fn is_ipv4_format(s: &str) -> bool {
+    s.parse::<Ipv4Addr>().is_ok()
+}
+
+fn is_ipv6_format(s: &str) -> bool {
+    s.parse::<Ipv6Addr>().is_ok()
+}
+
+fn is_email_format(s: &str) -> bool {
+    static EMAIL_RE: OnceLock<Regex> = OnceLock::new();
+    EMAIL_RE
+        .get_or_init(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap())
+        .is_match(s)
+}
+
+fn is_duration_format(s: &str) -> bool {
+    static DURATION_RE: OnceLock<Regex> = OnceLock::new();
+    DURATION_RE
+        .get_or_init(|| {
+            Regex::new(r"^P(?:\d+Y)?(?:\d+M)?(?:\d+D)?(?:T(?:\d+H)?(?:\d+M)?(?:\d+(?:\.\d+)?S)?)?$")
+                .unwrap()
+        })
+        .is_match(s)
+        && s != "P"
+        && s != "PT"
+}
+
+fn is_regex_format(s: &str) -> bool {
+    Regex::new(s).is_ok()
+}
+
+fn is_uuid_format(s: &str) -> bool {
+    static UUID_RE: OnceLock<Regex> = OnceLock::new();
+    UUID_RE
+        .get_or_init(|| {
+            Regex::new(
+                r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+            )
+            .unwrap()
+        })
+        .is_match(s)
+}
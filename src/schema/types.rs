@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::expr;
 
 /// Top-level schema structure for an envgen YAML schema file.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -7,10 +9,30 @@ use std::collections::BTreeMap;
 pub struct Schema {
     pub schema_version: String,
     pub metadata: Metadata,
-    pub environments: BTreeMap<String, BTreeMap<String, String>>,
+    pub environments: BTreeMap<String, EnvironmentConfig>,
     #[serde(default)]
     pub sources: BTreeMap<String, Source>,
     pub variables: BTreeMap<String, Variable>,
+
+    /// Other schema files (relative to this one) to inherit `environments`,
+    /// `sources`, and `variables` from. Resolved and merged away by
+    /// [`crate::schema::compose::load_composed_schema_file`] before
+    /// validation runs, so it is never present on a composed `Schema`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<Vec<String>>,
+}
+
+/// An environment's own template context, optionally layered on top of
+/// another environment via `extends`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EnvironmentConfig {
+    /// Name of another environment this one inherits config from. Keys
+    /// declared directly on this environment override the inherited ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+
+    #[serde(flatten)]
+    pub config: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -18,6 +40,12 @@ pub struct Schema {
 pub struct Metadata {
     pub description: String,
     pub destination: BTreeMap<String, String>,
+
+    /// Regex patterns matched against a command source's fully expanded
+    /// command string. A match may run unattended; anything else requires
+    /// interactive confirmation. See [`crate::resolver::safety`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command_allowlist: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -25,13 +53,20 @@ pub struct Metadata {
 pub struct Source {
     pub command: String,
 
-    #[serde(default)]
+    /// Expanded the same way as `command` (see `crate::template`) and piped
+    /// to the spawned command's stdin instead of substituted into the
+    /// command line, so a secret or lookup key it contains never shows up
+    /// in `ps` output or shell history.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin_template: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
@@ -46,57 +81,195 @@ pub struct Variable {
     /// Key into `sources`, or `static` / `manual`.
     ///
     /// For schema v2, this may be omitted when `resolvers` is used.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_key: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_instructions: Option<String>,
 
+    /// Manual source only: offered as a selection menu instead of free text.
+    /// Mutually exclusive with `pattern`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<String>>,
+
+    /// Manual source only: regex the entered value must match. Mutually
+    /// exclusive with `choices`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Manual source only: pre-filled/accept-on-enter answer, and the value
+    /// returned in non-interactive mode instead of skipping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
     /// Which environments this variable applies to. If None, applies to all.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub environments: Option<Vec<String>>,
 
     /// Inline values per environment (required when source = "static").
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub values: Option<BTreeMap<String, String>>,
 
     /// Schema v2: Per-environment resolver bindings for this variable.
     ///
     /// When present, the active resolver is selected by environment name.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resolvers: Option<Vec<VariableResolver>>,
 
     #[serde(default = "default_required")]
     pub required: bool,
 
-    #[serde(default)]
+    /// Rules a resolved value must satisfy; checked at pull time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub constraints: Option<Constraints>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
 }
 
+/// Validation rules enforced on a variable's resolved value.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Constraints {
+    /// Regex the resolved value must match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Allowed literal values.
+    #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enum_values: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<u64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u64>,
+
+    /// Minimum numeric value, for resolved values that parse as numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<f64>,
+
+    /// Maximum numeric value, for resolved values that parse as numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<f64>,
+}
+
+impl Constraints {
+    /// Checks `value` against this constraint set, returning the violated
+    /// rule as an error message on failure. Callers are expected to name
+    /// the variable themselves when surfacing the error.
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        if let Some(pattern) = &self.pattern {
+            let re = regex::Regex::new(pattern)
+                .map_err(|e| format!("constraint \"pattern\" is not a valid regex: {}", e))?;
+            if !re.is_match(value) {
+                return Err(format!("value does not match pattern \"{}\"", pattern));
+            }
+        }
+
+        if let Some(allowed) = &self.enum_values {
+            if !allowed.iter().any(|a| a == value) {
+                return Err(format!(
+                    "value \"{}\" is not one of the allowed values: {}",
+                    value,
+                    allowed.join(", ")
+                ));
+            }
+        }
+
+        if let Some(min_length) = self.min_length {
+            if (value.chars().count() as u64) < min_length {
+                return Err(format!(
+                    "value is shorter than the minimum length of {}",
+                    min_length
+                ));
+            }
+        }
+
+        if let Some(max_length) = self.max_length {
+            if (value.chars().count() as u64) > max_length {
+                return Err(format!(
+                    "value is longer than the maximum length of {}",
+                    max_length
+                ));
+            }
+        }
+
+        if self.minimum.is_some() || self.maximum.is_some() {
+            let parsed: f64 = value.parse().map_err(|_| {
+                format!(
+                    "value \"{}\" is not a number, but has a numeric constraint",
+                    value
+                )
+            })?;
+
+            if let Some(minimum) = self.minimum {
+                if parsed < minimum {
+                    return Err(format!(
+                        "value {} is less than the minimum of {}",
+                        parsed, minimum
+                    ));
+                }
+            }
+            if let Some(maximum) = self.maximum {
+                if parsed > maximum {
+                    return Err(format!(
+                        "value {} is greater than the maximum of {}",
+                        parsed, maximum
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct VariableResolver {
     pub environments: Vec<String>,
     pub source: String,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_key: Option<String>,
 
     /// Inline values per environment (required when source = "static").
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub values: Option<BTreeMap<String, String>>,
+
+    /// Manual source only: offered as a selection menu instead of free text.
+    /// Mutually exclusive with `pattern`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<String>>,
+
+    /// Manual source only: regex the entered value must match. Mutually
+    /// exclusive with `choices`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Manual source only: pre-filled/accept-on-enter answer, and the value
+    /// returned in non-interactive mode instead of skipping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// Gates this resolver on the environment's config: only applies when
+    /// this expression evaluates to true (see [`crate::schema::expr`]). A
+    /// resolver with no `when` always applies to the environments it lists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
 }
 
 fn default_sensitive() -> bool {
@@ -116,34 +289,95 @@ impl Variable {
         }
     }
 
-    /// Returns the resolver that applies to the given environment (schema v2).
-    pub fn resolver_for_env(&self, env: &str) -> Option<&VariableResolver> {
-        self.resolvers
-            .as_ref()
-            .and_then(|rs| rs.iter().find(|r| r.environments.iter().any(|e| e == env)))
+    /// Returns the resolver that applies to the given environment (schema v2):
+    /// the first one listing `env` whose `when` expression (if any) evaluates
+    /// to true against `env_config`. A resolver with no `when` always matches.
+    /// `validate_schema` rejects schemas where more than one resolver could
+    /// match the same environment, so "first" only matters for a malformed
+    /// `when` that failed to parse (treated as non-matching here).
+    pub fn resolver_for_env(
+        &self,
+        env: &str,
+        env_config: &BTreeMap<String, String>,
+    ) -> Option<&VariableResolver> {
+        let resolvers = self.resolvers.as_ref()?;
+        let ctx = crate::template::build_context(env, env_config, env);
+        resolvers.iter().find(|r| {
+            r.environments.iter().any(|e| e == env)
+                && match &r.when {
+                    None => true,
+                    Some(expr) => expr::eval_when(expr, &ctx),
+                }
+        })
     }
 
     /// Returns the effective source name for the given environment.
-    pub fn effective_source_for_env(&self, env: &str) -> Option<&str> {
-        self.resolver_for_env(env)
+    pub fn effective_source_for_env(
+        &self,
+        env: &str,
+        env_config: &BTreeMap<String, String>,
+    ) -> Option<&str> {
+        self.resolver_for_env(env, env_config)
             .map(|r| r.source.as_str())
             .or(self.source.as_deref())
     }
 
     /// Returns the key to use in source command templates.
-    pub fn effective_key_for_env(&self, var_name: &str, env: &str) -> String {
-        self.resolver_for_env(env)
+    pub fn effective_key_for_env(
+        &self,
+        var_name: &str,
+        env: &str,
+        env_config: &BTreeMap<String, String>,
+    ) -> String {
+        self.resolver_for_env(env, env_config)
             .and_then(|r| r.source_key.clone())
             .or_else(|| self.source_key.clone())
             .unwrap_or_else(|| var_name.to_string())
     }
 
     /// Returns the values map to use for the given environment (static source only).
-    pub fn values_for_env(&self, env: &str) -> Option<&BTreeMap<String, String>> {
-        self.resolver_for_env(env)
+    pub fn values_for_env(
+        &self,
+        env: &str,
+        env_config: &BTreeMap<String, String>,
+    ) -> Option<&BTreeMap<String, String>> {
+        self.resolver_for_env(env, env_config)
             .and_then(|r| r.values.as_ref())
             .or(self.values.as_ref())
     }
+
+    /// Returns the manual-source choices to use for the given environment.
+    pub fn choices_for_env(
+        &self,
+        env: &str,
+        env_config: &BTreeMap<String, String>,
+    ) -> Option<&[String]> {
+        self.resolver_for_env(env, env_config)
+            .and_then(|r| r.choices.as_deref())
+            .or(self.choices.as_deref())
+    }
+
+    /// Returns the manual-source validation pattern to use for the given environment.
+    pub fn pattern_for_env(
+        &self,
+        env: &str,
+        env_config: &BTreeMap<String, String>,
+    ) -> Option<&str> {
+        self.resolver_for_env(env, env_config)
+            .and_then(|r| r.pattern.as_deref())
+            .or(self.pattern.as_deref())
+    }
+
+    /// Returns the manual-source default answer to use for the given environment.
+    pub fn default_for_env(
+        &self,
+        env: &str,
+        env_config: &BTreeMap<String, String>,
+    ) -> Option<&str> {
+        self.resolver_for_env(env, env_config)
+            .and_then(|r| r.default.as_deref())
+            .or(self.default.as_deref())
+    }
 }
 
 impl Schema {
@@ -156,4 +390,77 @@ impl Schema {
     pub fn destination_for(&self, env: &str) -> Option<&String> {
         self.metadata.destination.get(env)
     }
+
+    /// Resolves the effective template context for `env` by walking its
+    /// `extends` chain from the root parent down to `env` itself, merging
+    /// each environment's own keys over its parent's (child keys win).
+    ///
+    /// Returns an error describing the problem if `env` is undefined, the
+    /// chain references an undefined parent, or the chain cycles back on
+    /// itself.
+    pub fn resolved_env_config(&self, env: &str) -> Result<BTreeMap<String, String>, String> {
+        let mut chain = Vec::new();
+        let mut seen = BTreeSet::new();
+        let mut current = env.to_string();
+
+        loop {
+            if !seen.insert(current.clone()) {
+                return Err(format!(
+                    "environment \"{}\" has a circular `extends` chain (revisits \"{}\").",
+                    env, current
+                ));
+            }
+
+            let config = self.environments.get(&current).ok_or_else(|| {
+                if current == env {
+                    format!("environment \"{}\" is not defined.", env)
+                } else {
+                    format!(
+                        "environment \"{}\" extends undefined environment \"{}\".",
+                        env, current
+                    )
+                }
+            })?;
+            chain.push(current.clone());
+
+            match &config.extends {
+                Some(parent) => current = parent.clone(),
+                None => break,
+            }
+        }
+
+        let mut merged = BTreeMap::new();
+        for name in chain.iter().rev() {
+            merged.extend(self.environments[name].config.clone());
+        }
+        Ok(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `min_length`/`max_length` count Unicode codepoints, not UTF-8 bytes
+    /// (matching `structural.rs`'s JSON-Schema `minLength`/`maxLength`), so
+    /// a value with multi-byte characters isn't measured by its encoded
+    /// size.
+    #[test]
+    fn test_length_constraints_count_chars_not_bytes() {
+        let constraints = Constraints {
+            pattern: None,
+            enum_values: None,
+            min_length: Some(3),
+            max_length: Some(3),
+            minimum: None,
+            maximum: None,
+        };
+
+        // 3 codepoints, but more than 3 UTF-8 bytes each for "é" (2 bytes)
+        // and "🎉" (4 bytes) — a byte-count check would reject this value
+        // as too long, and a value within the byte count but short on
+        // codepoints would wrongly pass.
+        assert!(constraints.check("é🎉x").is_ok());
+        assert!(constraints.check("ab").is_err());
+    }
 }
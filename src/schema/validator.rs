@@ -1,21 +1,86 @@
+use super::expr;
 use super::types::Schema;
 use crate::template;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Placeholders missing from `env_config`, split by whether they carry a
+/// `{name:?message}` modifier (which gets its own verbatim error line) or
+/// not (which feeds the generic "Fix: add environments.X.Y" message via
+/// [`format_unresolved_template_error`]). A `{name:-default}` modifier is
+/// never reported, since it's always resolvable.
+struct MissingPlaceholders {
+    generic: Vec<String>,
+    required_messages: Vec<String>,
+}
 
 fn unresolved_template_placeholders(
     template_str: &str,
     env_config: &std::collections::BTreeMap<String, String>,
-) -> Vec<String> {
-    let mut missing: BTreeSet<String> = BTreeSet::new();
+) -> MissingPlaceholders {
+    let mut generic: BTreeSet<String> = BTreeSet::new();
+    let mut required_messages = Vec::new();
     for ph in template::extract_placeholders(template_str) {
-        if ph == "key" || ph == "environment" {
+        if ph.name == "key" || ph.name == "environment" {
             continue;
         }
-        if !env_config.contains_key(&ph) {
-            missing.insert(ph);
+        if env_config.contains_key(&ph.name) {
+            continue;
+        }
+        match ph.modifier {
+            template::PlaceholderModifier::Default(_) => {}
+            template::PlaceholderModifier::Required(message) => required_messages.push(message),
+            template::PlaceholderModifier::None => {
+                generic.insert(ph.name);
+            }
+            // `{var:name}` references another variable's resolved value, not
+            // an `env_config` key; validated separately by `dependency`.
+            template::PlaceholderModifier::VariableRef => {}
+        }
+    }
+    MissingPlaceholders {
+        generic: generic.into_iter().collect(),
+        required_messages,
+    }
+}
+
+/// Validates a manual variable's (or resolver's) `choices`/`pattern`/`default`
+/// fields, pushing any errors found onto `errors` prefixed with `context`.
+fn check_manual_input_fields(
+    errors: &mut Vec<String>,
+    context: &str,
+    choices: Option<&[String]>,
+    pattern: Option<&str>,
+    default: Option<&str>,
+) {
+    if choices.is_some() && pattern.is_some() {
+        errors.push(format!(
+            "{}: cannot set both \"choices\" and \"pattern\". Choose one.",
+            context
+        ));
+    }
+
+    if let Some(choices) = choices {
+        if choices.iter().any(|c| c.trim().is_empty()) {
+            errors.push(format!(
+                "{}: \"choices\" entries must not be empty.",
+                context
+            ));
+        }
+    }
+
+    if let Some(default) = default {
+        if default.trim().is_empty() {
+            errors.push(format!("{}: \"default\" must not be empty.", context));
+        }
+        if let Some(choices) = choices {
+            if !choices.iter().any(|c| c == default) {
+                errors.push(format!(
+                    "{}: \"default\" value \"{}\" is not one of \"choices\".",
+                    context, default
+                ));
+            }
         }
     }
-    missing.into_iter().collect()
 }
 
 fn format_unresolved_template_error(
@@ -80,6 +145,22 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
 
     let env_names: Vec<&String> = schema.environments.keys().collect();
 
+    // Resolve each environment's `extends` chain up front so later checks
+    // can use the effective, post-merge config. An environment whose chain
+    // cycles or references an undefined parent is reported here and
+    // excluded from `resolved_env_configs`, so downstream checks simply
+    // skip template-placeholder validation for it rather than cascading
+    // unrelated "unresolved placeholder" errors.
+    let mut resolved_env_configs: HashMap<String, BTreeMap<String, String>> = HashMap::new();
+    for env_name in &env_names {
+        match schema.resolved_env_config(env_name) {
+            Ok(config) => {
+                resolved_env_configs.insert((*env_name).clone(), config);
+            }
+            Err(e) => errors.push(format!("environments.{}: {}", env_name, e)),
+        }
+    }
+
     // Validate each variable
     for (var_name, var) in &schema.variables {
         // Check description is not empty
@@ -87,6 +168,45 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
             errors.push(format!("{}: description must not be empty.", var_name));
         }
 
+        // Check constraint definitions are themselves well-formed
+        if let Some(constraints) = &var.constraints {
+            if let Some(pattern) = &constraints.pattern {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    errors.push(format!(
+                        "{}: constraints.pattern \"{}\" is not a valid regex: {}",
+                        var_name, pattern, e
+                    ));
+                }
+            }
+            if let (Some(min_length), Some(max_length)) =
+                (constraints.min_length, constraints.max_length)
+            {
+                if min_length > max_length {
+                    errors.push(format!(
+                        "{}: constraints.min_length ({}) is greater than constraints.max_length ({}).",
+                        var_name, min_length, max_length
+                    ));
+                }
+            }
+            if let (Some(minimum), Some(maximum)) = (constraints.minimum, constraints.maximum) {
+                if minimum > maximum {
+                    errors.push(format!(
+                        "{}: constraints.minimum ({}) is greater than constraints.maximum ({}).",
+                        var_name, minimum, maximum
+                    ));
+                }
+            }
+        }
+
+        // Check manual-source input constraints are well-formed
+        check_manual_input_fields(
+            &mut errors,
+            var_name,
+            var.choices.as_deref(),
+            var.pattern.as_deref(),
+            var.default.as_deref(),
+        );
+
         // Check environments references
         if let Some(var_envs) = &var.environments {
             for env in var_envs {
@@ -120,11 +240,10 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
                 ));
             }
 
-            let mut env_to_resolver: HashMap<String, usize> = HashMap::new();
             let resolvers = var.resolvers.as_ref().unwrap();
 
             for (idx, resolver) in resolvers.iter().enumerate() {
-                // Check resolver environments references + overlaps
+                // Check resolver environments references
                 if resolver.environments.is_empty() {
                     errors.push(format!(
                         "{}: resolver #{} must specify at least one environment.",
@@ -146,19 +265,35 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
                             var_name, env
                         ));
                     }
-                    if env_to_resolver.contains_key(env) {
+                }
+
+                // Check `when` is a well-formed expression
+                if let Some(when) = &resolver.when {
+                    if let Err(e) = expr::parse(when) {
                         errors.push(format!(
-                            "{}: resolver environments overlap for environment \"{}\".",
-                            var_name, env
+                            "variables.{}.resolvers[{}].when: {}",
+                            var_name,
+                            idx + 1,
+                            e
                         ));
-                    } else {
-                        env_to_resolver.insert(env.clone(), idx);
                     }
                 }
 
+                // Check manual-source input constraints are well-formed
+                check_manual_input_fields(
+                    &mut errors,
+                    &format!("{}: resolver #{}", var_name, idx + 1),
+                    resolver.choices.as_deref(),
+                    resolver.pattern.as_deref(),
+                    resolver.default.as_deref(),
+                );
+
                 // Check resolver source is valid
                 let source = resolver.source.as_str();
-                if source != "static" && source != "manual" && !schema.sources.contains_key(source)
+                if source != "static"
+                    && source != "manual"
+                    && source != "env"
+                    && !schema.sources.contains_key(source)
                 {
                     errors.push(format!(
                         "{}: resolver source \"{}\" is not defined in sources.",
@@ -183,7 +318,7 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
                                     continue;
                                 }
 
-                                if let Some(env_config) = schema.environments.get(env) {
+                                if let Some(env_config) = resolved_env_configs.get(env) {
                                     let value = values.get(env).unwrap();
                                     let missing =
                                         unresolved_template_placeholders(value, env_config);
@@ -193,11 +328,14 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
                                         idx + 1,
                                         env
                                     );
+                                    for message in &missing.required_messages {
+                                        errors.push(format!("{}: {}", yaml_path, message));
+                                    }
                                     if let Some(msg) = format_unresolved_template_error(
                                         &yaml_path,
                                         "static resolver value",
                                         env,
-                                        &missing,
+                                        &missing.generic,
                                     ) {
                                         errors.push(msg);
                                     }
@@ -208,10 +346,10 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
                 }
 
                 // Check source command template placeholders can be resolved (resolver-level)
-                if source != "static" && source != "manual" {
+                if source != "static" && source != "manual" && source != "env" {
                     if let Some(src) = schema.sources.get(source) {
                         for env_name in &resolver.environments {
-                            if let Some(env_config) = schema.environments.get(env_name) {
+                            if let Some(env_config) = resolved_env_configs.get(env_name) {
                                 let mut available_keys: Vec<String> =
                                     env_config.keys().cloned().collect();
                                 available_keys.push("key".to_string());
@@ -219,11 +357,21 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
 
                                 let placeholders = template::extract_placeholders(&src.command);
                                 for ph in placeholders {
-                                    if !available_keys.contains(&ph) {
-                                        errors.push(format!(
-                                            "{}: source command template references placeholder \"{{{}}}\" which cannot be resolved for environment \"{}\".",
-                                            var_name, ph, env_name
-                                        ));
+                                    if available_keys.contains(&ph.name) {
+                                        continue;
+                                    }
+                                    match ph.modifier {
+                                        template::PlaceholderModifier::Default(_) => {}
+                                        template::PlaceholderModifier::Required(message) => {
+                                            errors.push(format!("{}: {}", var_name, message));
+                                        }
+                                        template::PlaceholderModifier::VariableRef => {}
+                                        template::PlaceholderModifier::None => {
+                                            errors.push(format!(
+                                                "{}: source command template references placeholder \"{{{}}}\" which cannot be resolved for environment \"{}\".",
+                                                var_name, ph.name, env_name
+                                            ));
+                                        }
                                     }
                                 }
                             }
@@ -232,13 +380,41 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
                 }
             }
 
-            // Ensure all applicable environments are covered by exactly one resolver
+            // Ensure each applicable environment is matched by exactly one
+            // resolver, evaluated against that environment's own config so a
+            // `when`-gated resolver only counts where it would actually
+            // apply. A resolver with an unparseable `when` (already reported
+            // above) matches nothing here, same as at resolve time.
             for env in applicable_envs {
-                if !env_to_resolver.contains_key(env) {
+                let matching: Vec<usize> = resolvers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, r)| r.environments.iter().any(|e| e == env))
+                    .filter(|(_, r)| match &r.when {
+                        None => true,
+                        Some(when) => match resolved_env_configs.get(env) {
+                            Some(env_config) => {
+                                let ctx = template::build_context(env, env_config, env);
+                                expr::parse(when)
+                                    .map(|parsed| expr::evaluate(&parsed, &ctx))
+                                    .unwrap_or(false)
+                            }
+                            None => false,
+                        },
+                    })
+                    .map(|(idx, _)| idx)
+                    .collect();
+
+                if matching.is_empty() {
                     errors.push(format!(
                         "{}: no resolver provided for environment \"{}\".",
                         var_name, env
                     ));
+                } else if matching.len() > 1 {
+                    errors.push(format!(
+                        "{}: resolver environments overlap for environment \"{}\".",
+                        var_name, env
+                    ));
                 }
             }
         } else {
@@ -252,7 +428,7 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
             };
 
             // Check source is valid
-            if source != "static" && source != "manual" && !schema.sources.contains_key(source) {
+            if source != "static" && source != "manual" && source != "env" && !schema.sources.contains_key(source) {
                 errors.push(format!(
                     "{}: source \"{}\" is not defined in sources.",
                     var_name, source
@@ -278,16 +454,19 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
                                 continue;
                             }
 
-                            if let Some(env_config) = schema.environments.get(*env) {
+                            if let Some(env_config) = resolved_env_configs.get(*env) {
                                 let value = values.get(*env).unwrap();
                                 let missing = unresolved_template_placeholders(value, env_config);
                                 let yaml_path =
                                     format!("variables.{}.values.{}", var_name, env);
+                                for message in &missing.required_messages {
+                                    errors.push(format!("{}: {}", yaml_path, message));
+                                }
                                 if let Some(msg) = format_unresolved_template_error(
                                     &yaml_path,
                                     "static value",
                                     env,
-                                    &missing,
+                                    &missing.generic,
                                 ) {
                                     errors.push(msg);
                                 }
@@ -298,10 +477,10 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
             }
 
             // Check source command template placeholders can be resolved
-            if source != "static" && source != "manual" {
+            if source != "static" && source != "manual" && source != "env" {
                 if let Some(src) = schema.sources.get(source) {
                     for env_name in &applicable_envs {
-                        if let Some(env_config) = schema.environments.get(*env_name) {
+                        if let Some(env_config) = resolved_env_configs.get(*env_name) {
                             let mut available_keys: Vec<String> =
                                 env_config.keys().cloned().collect();
                             available_keys.push("key".to_string());
@@ -309,11 +488,22 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
 
                             let placeholders = template::extract_placeholders(&src.command);
                             for ph in placeholders {
-                                if !available_keys.contains(&ph) {
-                                    errors.push(format!(
-                                        "{}: source command template references placeholder \"{{{}}}\" which cannot be resolved for environment \"{}\".",
-                                        var_name, ph, env_name
-                                    ));
+                                if available_keys.contains(&ph.name) {
+                                    continue;
+                                }
+                                match ph.modifier {
+                                    template::PlaceholderModifier::Default(_) => {}
+                                    template::PlaceholderModifier::Required(message) => {
+                                        errors.push(format!("{}: {}", var_name, message));
+                                    }
+                                    template::PlaceholderModifier::None => {
+                                        errors.push(format!(
+                                            "{}: source command template references placeholder \"{{{}}}\" which cannot be resolved for environment \"{}\".",
+                                            var_name, ph.name, env_name
+                                        ));
+                                    }
+                                    // Validated separately by `dependency`.
+                                    template::PlaceholderModifier::VariableRef => {}
                                 }
                             }
                         }
@@ -323,6 +513,13 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
         }
     }
 
+    // Cross-variable `{var:X}` references: detect undefined/inapplicable
+    // references and reference cycles, per environment.
+    for env_name in &env_names {
+        let analysis = super::dependency::analyze(schema, env_name);
+        errors.extend(analysis.errors);
+    }
+
     // Check that built-in source names are not redefined
     if schema.sources.contains_key("static") {
         errors.push("Source name \"static\" is built-in and must not be redefined.".to_string());
@@ -330,6 +527,9 @@ pub fn validate_schema(schema: &Schema) -> Vec<String> {
     if schema.sources.contains_key("manual") {
         errors.push("Source name \"manual\" is built-in and must not be redefined.".to_string());
     }
+    if schema.sources.contains_key("env") {
+        errors.push("Source name \"env\" is built-in and must not be redefined.".to_string());
+    }
 
     errors
 }
@@ -603,6 +803,237 @@ variables:
         );
     }
 
+    #[test]
+    fn test_static_value_default_placeholder_is_never_unresolved() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: static
+    values:
+      local: "{region:-us-east-1}"
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            !errors.iter().any(|e| e.contains("unresolved template placeholder")),
+            "Expected no unresolved placeholder error for a {{region:-us-east-1}} default, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_source_command_required_placeholder_reports_custom_message() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources:
+  my-source:
+    command: "curl {api_host:?must set api_host for this env}"
+variables:
+  FOO:
+    description: "A variable"
+    source: my-source
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("must set api_host for this env")),
+            "Expected custom required-placeholder message, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_constraint_invalid_regex() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: static
+    values:
+      local: "bar"
+    constraints:
+      pattern: "("
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("constraints.pattern") && e.contains("not a valid regex")),
+            "Expected invalid regex error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_constraint_min_length_greater_than_max_length() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: static
+    values:
+      local: "bar"
+    constraints:
+      min_length: 10
+      max_length: 5
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("constraints.min_length") && e.contains("constraints.max_length")),
+            "Expected min_length/max_length ordering error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_env_source_is_valid_without_values_or_sources_entry() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: env
+"#;
+        let errors = errors_for(yaml);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_env_source_name_cannot_be_redefined() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    project: "test"
+sources:
+  env:
+    command: "echo {key}"
+variables:
+  FOO:
+    description: "A variable"
+    source: env
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("\"env\" is built-in and must not be redefined")),
+            "Expected built-in redefinition error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_extends_undefined_parent() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    extends: nonexistent
+    project: "test"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: static
+    values:
+      local: "bar"
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("extends undefined environment \"nonexistent\"")),
+            "Expected undefined-parent error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_extends_cycle() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    extends: staging
+    project: "test"
+  staging:
+    extends: local
+    project: "test-staging"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: static
+    values:
+      local: "bar"
+      staging: "bar"
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors.iter().any(|e| e.contains("circular `extends` chain")),
+            "Expected circular extends error, got: {:?}",
+            errors
+        );
+    }
+
     #[test]
     fn test_undefined_source() {
         let yaml = r#"
@@ -647,6 +1078,204 @@ variables:
         assert!(errors.iter().any(|e| e.contains("no values map")));
     }
 
+    #[test]
+    fn test_manual_cannot_set_choices_and_pattern() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local: {}
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: manual
+    choices: ["a", "b"]
+    pattern: "^[a-z]+$"
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors.iter().any(|e| e.contains("cannot set both")
+                && e.contains("\"choices\"")
+                && e.contains("\"pattern\"")),
+            "Expected choices+pattern conflict error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_manual_choices_entry_must_not_be_empty() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local: {}
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: manual
+    choices: ["a", ""]
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("\"choices\" entries must not be empty")),
+            "Expected empty choices entry error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_manual_default_must_be_one_of_choices() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local: {}
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: manual
+    choices: ["a", "b"]
+    default: "c"
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("\"default\"") && e.contains("not one of \"choices\"")),
+            "Expected default-not-in-choices error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_manual_with_valid_choices_and_default_has_no_errors() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local: {}
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    source: manual
+    choices: ["a", "b"]
+    default: "a"
+"#;
+        let errors = errors_for(yaml);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
+    #[test]
+    fn test_resolver_malformed_when_expression_is_an_error() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local: {}
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    resolvers:
+      - environments: [local]
+        source: static
+        values:
+          local: "a"
+        when: "tier =="
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("variables.FOO.resolvers[1].when")),
+            "Expected a when-expression parse error, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_resolvers_with_when_that_can_both_match_are_overlapping() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    tier: "paid"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    resolvers:
+      - environments: [local]
+        source: static
+        values:
+          local: "a"
+        when: "tier == \"paid\""
+      - environments: [local]
+        source: static
+        values:
+          local: "b"
+"#;
+        let errors = errors_for(yaml);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.contains("resolver environments overlap") && e.contains("local")),
+            "Expected overlap error since both resolvers match local, got: {:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn test_resolver_with_when_gating_sole_coverage_has_no_errors() {
+        let yaml = r#"
+schema_version: "2"
+metadata:
+  description: "Test"
+  destination:
+    local: ".env"
+environments:
+  local:
+    tier: "paid"
+sources: {}
+variables:
+  FOO:
+    description: "A variable"
+    resolvers:
+      - environments: [local]
+        source: static
+        values:
+          local: "a"
+        when: "tier == \"paid\""
+"#;
+        let errors = errors_for(yaml);
+        assert!(errors.is_empty(), "Expected no errors, got: {:?}", errors);
+    }
+
     #[test]
     fn test_undefined_environment_in_variable() {
         let yaml = r#"
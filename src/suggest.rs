@@ -0,0 +1,55 @@
+/// Find the candidate closest to `input` by edit distance, for "did you mean"
+/// hints on typo'd environment/source names.
+///
+/// Mirrors the heuristic Cargo's `lev_distance` suggestions use: only return
+/// a match when its distance is within `max(input.len() / 3, 2)`, so names
+/// that are nothing alike don't produce a misleading suggestion.
+pub fn closest<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let max_distance = std::cmp::max(input.chars().count() / 3, 2);
+
+    candidates
+        .map(|candidate| (candidate, edit_distance(input, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// Classic two-row dynamic-programming edit (Levenshtein) distance.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur: Vec<usize> = vec![0; b.len() + 1];
+
+    for (i, &ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            cur[j + 1] = std::cmp::min(
+                std::cmp::min(cur[j] + 1, prev[j + 1] + 1),
+                prev[j] + cost,
+            );
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::closest;
+
+    #[test]
+    fn suggests_single_typo() {
+        let candidates = ["staging", "production", "local"];
+        assert_eq!(closest("stagng", candidates.into_iter()), Some("staging"));
+    }
+
+    #[test]
+    fn no_suggestion_when_nothing_close() {
+        let candidates = ["staging", "production", "local"];
+        assert_eq!(closest("xyz", candidates.into_iter()), None);
+    }
+}
@@ -2,39 +2,180 @@ use anyhow::{bail, Result};
 use regex::Regex;
 use std::collections::HashMap;
 
-/// Extract all placeholder names from a template string.
-/// Placeholders are in the form `{name}`.
-pub fn extract_placeholders(template: &str) -> Vec<String> {
-    let re = Regex::new(r"\{([a-zA-Z_][a-zA-Z0-9_]*)\}").unwrap();
+/// A `{name}` placeholder, with its optional Bash/Compose-style modifier,
+/// or a `{var:name}` cross-reference to another schema variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub modifier: PlaceholderModifier,
+}
+
+/// A placeholder's modifier, mirroring `${VAR:-default}` / `${VAR:?message}`
+/// shell parameter expansion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlaceholderModifier {
+    /// Plain `{name}`: missing from context is unresolved.
+    None,
+    /// `{name:-default}`: falls back to `default` (which may be empty)
+    /// instead of ever being considered unresolved.
+    Default(String),
+    /// `{name:?message}`: unresolved if missing, but reports `message`
+    /// verbatim instead of the generic "unresolved placeholder" text.
+    Required(String),
+    /// `{var:name}`: resolves to another variable's resolved value rather
+    /// than `env_config`. Cross-variable refs are validated and ordered
+    /// separately — see [`crate::schema::dependency`] — rather than treated
+    /// as an ordinary unresolved-placeholder error.
+    VariableRef,
+}
+
+/// Matches `{var:name}`, `{name}`, `{name:-default}`, and `{name:?message}`.
+/// Group 1 is the `var:` name, group 2 the plain name, group 3 the `:-`
+/// default, group 4 the `:?` message. `[^}]*` lets the default/message
+/// contain `:` or `-` themselves.
+fn placeholder_regex() -> Regex {
+    Regex::new(
+        r"\{(?:var:([a-zA-Z_][a-zA-Z0-9_]*)|([a-zA-Z_][a-zA-Z0-9_]*)(?::-([^}]*)|:\?([^}]*))?)\}",
+    )
+    .unwrap()
+}
+
+/// Extract all placeholders from a template string: `{name}`,
+/// `{name:-default}`, `{name:?message}`, or `{var:name}`. `${VAR}`-style
+/// OS-environment references (see [`expand_template`]) are resolved
+/// separately and are not returned here, since they don't need to appear in
+/// `env_config`.
+pub fn extract_placeholders(template: &str) -> Vec<Placeholder> {
+    let re = placeholder_regex();
     re.captures_iter(template)
-        .map(|cap| cap[1].to_string())
+        .filter(|cap| !preceded_by_dollar(template, cap.get(0).unwrap().start()))
+        .map(|cap| {
+            if let Some(var_name) = cap.get(1) {
+                return Placeholder {
+                    name: var_name.as_str().to_string(),
+                    modifier: PlaceholderModifier::VariableRef,
+                };
+            }
+            let modifier = if let Some(default) = cap.get(3) {
+                PlaceholderModifier::Default(default.as_str().to_string())
+            } else if let Some(message) = cap.get(4) {
+                PlaceholderModifier::Required(message.as_str().to_string())
+            } else {
+                PlaceholderModifier::None
+            };
+            Placeholder {
+                name: cap[2].to_string(),
+                modifier,
+            }
+        })
         .collect()
 }
 
+fn preceded_by_dollar(template: &str, pos: usize) -> bool {
+    pos > 0 && template.as_bytes()[pos - 1] == b'$'
+}
+
+/// The context key under which [`set_variable_value`] stores a resolved
+/// variable's value, so a later `{var:name}` reference can find it without
+/// colliding with an `env_config` key of the same name.
+fn variable_ref_key(name: &str) -> String {
+    format!("var:{}", name)
+}
+
+/// Registers `name`'s resolved value in `ctx` so that a later `{var:name}`
+/// reference in another variable's template can find it.
+pub fn set_variable_value(ctx: &mut HashMap<String, String>, name: &str, value: &str) {
+    ctx.insert(variable_ref_key(name), value.to_string());
+}
+
 /// Expand all `{placeholder}` references in a template string using the provided context.
-/// Returns an error if any placeholder cannot be resolved.
+/// Returns an error if any placeholder cannot be resolved: a `{name:-default}`
+/// placeholder never errors (it falls back to `default`), a `{name:?message}`
+/// placeholder reports `message` verbatim instead of its name, and a
+/// `{var:name}` reference resolves against values registered via
+/// [`set_variable_value`].
 pub fn expand_template(template: &str, context: &HashMap<String, String>) -> Result<String> {
-    let re = Regex::new(r"\{([a-zA-Z_][a-zA-Z0-9_]*)\}").unwrap();
+    let template = expand_os_env_vars(template)?;
+
+    let re = placeholder_regex();
 
     // First check all placeholders can be resolved
+    let mut unresolved = Vec::new();
+    for cap in re.captures_iter(&template) {
+        if let Some(var_name) = cap.get(1) {
+            if !context.contains_key(&variable_ref_key(var_name.as_str())) {
+                unresolved.push(format!("var:{}", var_name.as_str()));
+            }
+            continue;
+        }
+
+        let name = &cap[2];
+        if context.contains_key(name) {
+            continue;
+        }
+        if cap.get(3).is_some() {
+            continue; // has a `:-default`, so it's always resolvable
+        }
+        match cap.get(4) {
+            Some(message) => unresolved.push(message.as_str().to_string()),
+            None => unresolved.push(name.to_string()),
+        }
+    }
+
+    if !unresolved.is_empty() {
+        bail!(
+            "Unresolved template placeholders: {}",
+            unresolved.join(", ")
+        );
+    }
+
+    let result = re.replace_all(&template, |caps: &regex::Captures| {
+        if let Some(var_name) = caps.get(1) {
+            return context
+                .get(&variable_ref_key(var_name.as_str()))
+                .cloned()
+                .unwrap_or_default();
+        }
+        let name = &caps[2];
+        match context.get(name) {
+            Some(value) => value.clone(),
+            None => caps.get(3).map(|m| m.as_str().to_string()).unwrap_or_default(),
+        }
+    });
+
+    Ok(result.to_string())
+}
+
+/// Expand `${VAR}` and `${VAR:-default}` references against the process
+/// environment. Runs before `{name}` placeholder resolution so the inner
+/// braces of `${VAR}` are never mistaken for a schema placeholder.
+/// Errors only when a `${VAR}` has no default and isn't set.
+fn expand_os_env_vars(template: &str) -> Result<String> {
+    let re = Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)(:-([^}]*))?\}").unwrap();
+
     let mut unresolved = Vec::new();
     for cap in re.captures_iter(template) {
         let name = &cap[1];
-        if !context.contains_key(name) {
+        let has_default = cap.get(2).is_some();
+        if !has_default && std::env::var(name).is_err() {
             unresolved.push(name.to_string());
         }
     }
 
     if !unresolved.is_empty() {
         bail!(
-            "Unresolved template placeholders: {}",
+            "Unresolved OS environment variables: {}",
             unresolved.join(", ")
         );
     }
 
-    let result = re.replace_all(template, |caps: &regex::Captures| {
-        let name = &caps[1];
-        context.get(name).cloned().unwrap_or_default()
+    let result = re.replace_all(template, |caps: &regex::Captures| match std::env::var(&caps[1])
+    {
+        Ok(value) => value,
+        Err(_) => caps
+            .get(3)
+            .map(|m| m.as_str().to_string())
+            .unwrap_or_default(),
     });
 
     Ok(result.to_string())
@@ -60,15 +201,29 @@ pub fn build_context(
     ctx
 }
 
+/// Registers every entry of `resolved` (variable name -> resolved value) in
+/// `ctx` via [`set_variable_value`], so `{var:name}` references in the
+/// template being expanded next can find them.
+pub fn apply_resolved_variables(ctx: &mut HashMap<String, String>, resolved: &HashMap<String, String>) {
+    for (name, value) in resolved {
+        set_variable_value(ctx, name, value);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn names(placeholders: &[Placeholder]) -> Vec<&str> {
+        placeholders.iter().map(|p| p.name.as_str()).collect()
+    }
+
     #[test]
     fn test_extract_placeholders() {
         let template = "firebase functions:secrets:access {key} --project {firebase_project}";
         let placeholders = extract_placeholders(template);
-        assert_eq!(placeholders, vec!["key", "firebase_project"]);
+        assert_eq!(names(&placeholders), vec!["key", "firebase_project"]);
+        assert!(placeholders.iter().all(|p| p.modifier == PlaceholderModifier::None));
     }
 
     #[test]
@@ -94,6 +249,138 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_expand_os_env_var() {
+        // SAFETY: this test doesn't run concurrently with anything else
+        // that reads or writes this variable.
+        unsafe {
+            std::env::set_var("ENVGEN_TEMPLATE_TEST_VAR", "from-os-env");
+        }
+        let result = expand_template("echo ${ENVGEN_TEMPLATE_TEST_VAR}", &HashMap::new()).unwrap();
+        assert_eq!(result, "echo from-os-env");
+    }
+
+    #[test]
+    fn test_expand_os_env_var_default_when_unset() {
+        // SAFETY: this test doesn't run concurrently with anything else
+        // that reads or writes this variable.
+        unsafe {
+            std::env::remove_var("ENVGEN_TEMPLATE_TEST_MISSING");
+        }
+        let result = expand_template(
+            "echo ${ENVGEN_TEMPLATE_TEST_MISSING:-fallback}",
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(result, "echo fallback");
+    }
+
+    #[test]
+    fn test_expand_os_env_var_unresolved_without_default() {
+        // SAFETY: this test doesn't run concurrently with anything else
+        // that reads or writes this variable.
+        unsafe {
+            std::env::remove_var("ENVGEN_TEMPLATE_TEST_MISSING_NO_DEFAULT");
+        }
+        let result = expand_template(
+            "echo ${ENVGEN_TEMPLATE_TEST_MISSING_NO_DEFAULT}",
+            &HashMap::new(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_expand_template_mixes_os_and_schema_placeholders() {
+        // SAFETY: this test doesn't run concurrently with anything else
+        // that reads or writes this variable.
+        unsafe {
+            std::env::set_var("ENVGEN_TEMPLATE_TEST_HOST", "ci-runner");
+        }
+        let mut ctx = HashMap::new();
+        ctx.insert("key".to_string(), "MY_SECRET".to_string());
+
+        let result =
+            expand_template("fetch {key} --host ${ENVGEN_TEMPLATE_TEST_HOST}", &ctx).unwrap();
+        assert_eq!(result, "fetch MY_SECRET --host ci-runner");
+    }
+
+    #[test]
+    fn test_extract_placeholders_ignores_os_env_vars() {
+        let placeholders =
+            extract_placeholders("echo {key} --host ${ENVGEN_TEMPLATE_TEST_HOST}");
+        assert_eq!(names(&placeholders), vec!["key"]);
+    }
+
+    #[test]
+    fn test_extract_placeholders_with_modifiers() {
+        let placeholders =
+            extract_placeholders("{region:-us-east-1} {api_host:?must set api_host for this env} {plain}");
+        assert_eq!(
+            placeholders,
+            vec![
+                Placeholder {
+                    name: "region".to_string(),
+                    modifier: PlaceholderModifier::Default("us-east-1".to_string()),
+                },
+                Placeholder {
+                    name: "api_host".to_string(),
+                    modifier: PlaceholderModifier::Required(
+                        "must set api_host for this env".to_string()
+                    ),
+                },
+                Placeholder {
+                    name: "plain".to_string(),
+                    modifier: PlaceholderModifier::None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_value_survives_colons_and_dashes() {
+        let placeholders = extract_placeholders("{db_url:-postgres://localhost:5432/app-db}");
+        assert_eq!(
+            placeholders[0].modifier,
+            PlaceholderModifier::Default("postgres://localhost:5432/app-db".to_string())
+        );
+    }
+
+    #[test]
+    fn test_expand_template_uses_default_when_unset() {
+        let result = expand_template("--region {region:-us-east-1}", &HashMap::new()).unwrap();
+        assert_eq!(result, "--region us-east-1");
+    }
+
+    #[test]
+    fn test_expand_template_empty_default() {
+        let result = expand_template("prefix-{suffix:-}", &HashMap::new()).unwrap();
+        assert_eq!(result, "prefix-");
+    }
+
+    #[test]
+    fn test_expand_template_context_overrides_default() {
+        let mut ctx = HashMap::new();
+        ctx.insert("region".to_string(), "eu-west-1".to_string());
+        let result = expand_template("--region {region:-us-east-1}", &ctx).unwrap();
+        assert_eq!(result, "--region eu-west-1");
+    }
+
+    #[test]
+    fn test_expand_template_required_reports_custom_message() {
+        let err = expand_template("{api_host:?must set api_host for this env}", &HashMap::new())
+            .unwrap_err();
+        assert!(err.to_string().contains("must set api_host for this env"));
+    }
+
+    #[test]
+    fn test_expand_template_required_resolves_when_present() {
+        let mut ctx = HashMap::new();
+        ctx.insert("api_host".to_string(), "example.com".to_string());
+        let result =
+            expand_template("{api_host:?must set api_host for this env}", &ctx).unwrap();
+        assert_eq!(result, "example.com");
+    }
+
     #[test]
     fn test_build_context() {
         let mut env_config = std::collections::BTreeMap::new();
@@ -104,4 +391,39 @@ mod tests {
         assert_eq!(ctx.get("key").unwrap(), "MY_KEY");
         assert_eq!(ctx.get("firebase_project").unwrap(), "my-proj");
     }
+
+    #[test]
+    fn test_extract_variable_ref_placeholder() {
+        let placeholders = extract_placeholders("{var:BASE_URL}/api");
+        assert_eq!(
+            placeholders,
+            vec![Placeholder {
+                name: "BASE_URL".to_string(),
+                modifier: PlaceholderModifier::VariableRef,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_expand_template_resolves_variable_ref() {
+        let mut ctx = HashMap::new();
+        set_variable_value(&mut ctx, "BASE_URL", "https://example.com");
+        let result = expand_template("{var:BASE_URL}/api", &ctx).unwrap();
+        assert_eq!(result, "https://example.com/api");
+    }
+
+    #[test]
+    fn test_expand_template_unresolved_variable_ref() {
+        let result = expand_template("{var:BASE_URL}/api", &HashMap::new());
+        assert!(result.unwrap_err().to_string().contains("var:BASE_URL"));
+    }
+
+    #[test]
+    fn test_variable_ref_does_not_collide_with_env_config_key_of_same_name() {
+        let mut ctx = HashMap::new();
+        ctx.insert("BASE_URL".to_string(), "from-env-config".to_string());
+        set_variable_value(&mut ctx, "BASE_URL", "from-variable");
+        let result = expand_template("{BASE_URL} {var:BASE_URL}", &ctx).unwrap();
+        assert_eq!(result, "from-env-config from-variable");
+    }
 }
@@ -115,6 +115,50 @@ fn test_init_force_overwrites() {
     assert_eq!(content, fixture_content());
 }
 
+#[test]
+fn test_init_format_toml() {
+    let tmp = TempDir::new().unwrap();
+    let output_path = tmp.path().join("env.dev.toml");
+
+    envgen()
+        .current_dir(tmp.path())
+        .arg("init")
+        .arg("--format")
+        .arg("toml")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote sample schema"));
+
+    let content = fs::read_to_string(&output_path).unwrap();
+    let schema: toml::Value = toml::from_str(&content).unwrap();
+    assert_eq!(
+        schema["variables"]["API_TOKEN"]["description"].as_str(),
+        Some("API token used for local development.")
+    );
+}
+
+#[test]
+fn test_init_format_json() {
+    let tmp = TempDir::new().unwrap();
+    let output_path = tmp.path().join("env.dev.json");
+
+    envgen()
+        .current_dir(tmp.path())
+        .arg("init")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote sample schema"));
+
+    let content = fs::read_to_string(&output_path).unwrap();
+    let schema: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(
+        schema["variables"]["API_TOKEN"]["description"].as_str(),
+        Some("API token used for local development.")
+    );
+}
+
 #[test]
 fn test_init_quiet_suppresses_output() {
     let tmp = TempDir::new().unwrap();
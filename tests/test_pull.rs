@@ -0,0 +1,73 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn envgen() -> Command {
+    cargo_bin_cmd!("envgen")
+}
+
+#[test]
+fn test_pull_dry_run_json_format_shape() {
+    envgen()
+        .arg("pull")
+        .arg("-c")
+        .arg("tests/fixtures/valid_frontend.yaml")
+        .arg("-e")
+        .arg("local")
+        .arg("--dry-run")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"variables\""))
+        .stdout(predicate::str::contains("\"summary\""))
+        .stdout(predicate::str::contains("\"total\""))
+        .stdout(predicate::str::contains("\"success\""))
+        .stdout(predicate::str::contains("\"exit_code\": 0"));
+}
+
+#[test]
+fn test_pull_dry_run_json_format_reports_nonzero_exit_code_on_failure() {
+    envgen()
+        .arg("pull")
+        .arg("-c")
+        .arg("tests/fixtures/semantic_invalid_schema.yaml")
+        .arg("-e")
+        .arg("local")
+        .arg("--dry-run")
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_pull_invalid_format_is_rejected() {
+    envgen()
+        .arg("pull")
+        .arg("-c")
+        .arg("tests/fixtures/valid_frontend.yaml")
+        .arg("-e")
+        .arg("local")
+        .arg("--dry-run")
+        .arg("--format")
+        .arg("csv")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown format"));
+}
+
+#[test]
+fn test_pull_watch_cannot_combine_with_dry_run() {
+    envgen()
+        .arg("pull")
+        .arg("-c")
+        .arg("tests/fixtures/valid_frontend.yaml")
+        .arg("-e")
+        .arg("local")
+        .arg("--dry-run")
+        .arg("--watch")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--watch cannot be combined with --dry-run"));
+}